@@ -37,7 +37,70 @@ pub enum NftError {
     AllBoxesMinted,
 
     #[error("Not all lots are drawn")]
-    NotDrawn
+    NotDrawn,
+
+    #[error("The auction is over")]
+    AuctionEnded,
+
+    #[error("The auction is not over yet")]
+    AuctionNotEnded,
+
+    #[error("Wrong sale settings")]
+    WrongSellSettings,
+
+    #[error("Amount overflow")]
+    AmountOverflow,
+
+    #[error("Amount underflow")]
+    AmountUnderflow,
+
+    #[error("This listing is reserved for another buyer")]
+    BuyerNotAllowed,
+
+    #[error("The listing has expired")]
+    ListingExpired,
+
+    #[error("The listing has not expired yet")]
+    ListingNotExpired,
+
+    #[error("Buy now is not available for this listing")]
+    BuyNowUnavailable,
+
+    #[error("Revealed bid does not match the commitment")]
+    WrongCommitment,
+
+    #[error("The reveal phase is not active")]
+    NotRevealPhase,
+
+    #[error("The listing does not allow withdrawing this bet now")]
+    BetWithdrawalNotAllowed,
+
+    #[error("The listed token has moved or the delegate approval was revoked")]
+    ListingInvalid,
+
+    #[error("The token is already listed")]
+    AlreadyListed,
+
+    #[error("Not enough units left in the listing")]
+    NotEnoughQuantity,
+
+    #[error("Token program must be SPL Token or Token-2022")]
+    WrongTokenProgram,
+
+    #[error("Non-transferable tokens cannot be listed")]
+    NonTransferableMint,
+
+    #[error("Payment mints with a transfer fee are not supported")]
+    TransferFeeNotSupported,
+
+    #[error("Unknown listing storage version")]
+    UnknownSellVersion,
+
+    #[error("Legacy auction can only be closed by the seller")]
+    LegacyListing,
+
+    #[error("Token is not in the offer collection")]
+    NotInCollection
 
 }
 
@@ -1,11 +1,13 @@
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
 use crate::{
 	types::{
 		metadata::CreateMetadataArgs,
 		lottery::Lottery,
 		sell::Sell,
 		nft_lottery::{NftStorage, Lot, SaveToken},
-		share::ShareData
+		share::ShareData,
+		fee::FeeConfig
 	}
 };
 
@@ -14,5 +16,30 @@ pub enum SolInstruction {
 	DelPDA,
 	BurnToken,
 	NftLotAdd {data: Lot},
-	Withdrawal
+	Withdrawal,
+	SettleAuction,
+	SetFeeConfig {data: FeeConfig},
+	CancelListing,
+	UpdateListing {price: u64},
+	MakeOffer {price: u64},
+	CancelOffer,
+	AcceptOffer,
+	MakeCollectionOffer {key: Pubkey, kind: u8, price: u64, quantity: u64},
+	CancelCollectionOffer,
+	FillCollectionOffer,
+	BundleCreate {sell: Sell, duration: u64},
+	BundleWithdrawal,
+	BundleSettle,
+	BuyNow,
+	CommitSealedBid {commitment: [u8; 32], deposit: u64},
+	RevealSealedBid {amount: u64, salt: [u8; 32]},
+	ClaimSealedBid,
+	SettleSealedBid,
+	CreateBidHistory,
+	CloseBidHistory,
+	SellDelegateCreate {sell: Sell, duration: u64},
+	SellDelegateBuy,
+	SellDelegateCancel,
+	SellBuy {amount: u64},
+	BundleCancel
 }
\ No newline at end of file
@@ -17,6 +17,18 @@ pub const METADATA_PROGRAM_ID: &str = "";
 pub const SETTINGS_SEED: &str = "";
 pub const PREFIX: &str = "";
 pub const STORAGE: &str = "";
+pub const ESCROW: &str = "escrow";
+pub const FEE_SEED: &str = "fee";
+pub const OFFER: &str = "offer";
+pub const COLLECTION_OFFER: &str = "collection_offer";
+pub const BUNDLE: &str = "bundle";
+pub const BUNDLE_MAX: usize = 5;
+pub const SEALED_BID: &str = "sealed_bid";
+pub const BID_HISTORY: &str = "bid_history";
+pub const BID_HISTORY_MAX: usize = 16;
+pub const REGISTRY: &str = "registry";
+pub const SELL_VERSION: u8 = 1;
+pub const LEGACY_SELL_LEN: usize = 121;
 
 pub const LOTTERY_SEED: &str = "";
 pub const LOTTERY_TOKEN: &str = "";
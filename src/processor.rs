@@ -7,8 +7,21 @@ use solana_program::{
 use crate::{
 	utils::{
 		lottery_create::process_create_lottery,
-		sell_create_storage::process_sell_create_storage,
+		sell_create::process_sell_create,
 		sell_withdrawal::process_sell_withdrawal,
+		sell_settle::process_sell_settle,
+		sell_buy_now::process_sell_buy_now,
+		sell_buy::process_sell_buy,
+		sealed_bid::{process_sealed_commit, process_sealed_reveal, process_sealed_claim, process_sealed_settle},
+		sell_cancel::process_sell_cancel,
+		sell_update::process_sell_update,
+		sell_delegate::{process_sell_delegate_create, process_sell_delegate_buy, process_sell_delegate_cancel},
+		offer::{process_offer_make, process_offer_cancel, process_offer_accept},
+		collection_offer::{process_collection_offer_make, process_collection_offer_cancel, process_collection_offer_fill},
+		bundle::{process_bundle_create, process_bundle_withdrawal, process_bundle_settle, process_bundle_cancel},
+		bid_history::{process_bid_history_create, process_bid_history_close},
+		fee_config::process_set_fee_config,
+		event::emit,
 		lottery_check::process_check_lottery,
 		del_pda::process_del_pda,
 		save_token::process_save_token,
@@ -26,6 +39,7 @@ use crate::{
 		create_mint::process_create_mint,
 		burn_token::process_burn_token
 	},
+	types::event::Event,
 	instruction::SolInstruction
 };
 pub struct Processor;
@@ -65,7 +79,7 @@ impl Processor {
 					data
 				)
 			},
-			SolInstruction::SellCreateStorage {sell} => {
+			SolInstruction::SellCreateStorage {sell, duration} => {
 				let account_info_iter = &mut accounts.iter();
 				let payer = next_account_info(account_info_iter)?;
 				let token_account = next_account_info(account_info_iter)?;
@@ -75,7 +89,8 @@ impl Processor {
 				let token_program = next_account_info(account_info_iter)?;
 				let rent_info = next_account_info(account_info_iter)?;
 				let system_program_info = next_account_info(account_info_iter)?;
-				process_sell_create_storage(
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_create(
 					program_id,
 					payer,
 					token_account,
@@ -85,7 +100,10 @@ impl Processor {
 					token_program,
 					rent_info,
 					system_program_info,
-					sell
+					registry,
+					account_info_iter.as_slice(),
+					sell,
+					duration
 				)
 			},
 			SolInstruction::SellWithdrawal => {
@@ -102,6 +120,10 @@ impl Processor {
 				let spl_token_program = next_account_info(account_info_iter)?;
 				let rent_program = next_account_info(account_info_iter)?;
 				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
 				process_sell_withdrawal(
 					program_id,
 					mint,
@@ -115,7 +137,12 @@ impl Processor {
 					token_program,
 					spl_token_program,
 					rent_program,
-					system_program
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					registry,
+					account_info_iter.as_slice()
 				)
 			},
 			SolInstruction::CreateLottery {lots} => {
@@ -153,7 +180,12 @@ impl Processor {
 					lottery_account,
 					profit_id,
 					token_program
-				)
+				)?;
+				emit(Event::LotteryBoxOpened {
+					lottery: *lottery_account.key,
+					mint: *mint.key,
+					owner: *payer.key
+				})
 			},
 			SolInstruction::NftCreateCollection {data} => {
 				let account_info_iter = &mut accounts.iter();
@@ -185,7 +217,11 @@ impl Processor {
 					rent_program,
 					system_program,
 					data
-				)
+				)?;
+				emit(Event::LotAdded {
+					payer: *payer.key,
+					storage: *storage.key
+				})
 			},
 			SolInstruction::NftLotteryGetToken {data} => {
 				let account_info_iter = &mut accounts.iter();
@@ -222,7 +258,12 @@ impl Processor {
 					rent_program,
 					system_program,
 					data
-				)
+				)?;
+				emit(Event::LotteryBoxOpened {
+					lottery: *storage.key,
+					mint: *box_mint.key,
+					owner: *payer.key
+				})
 			},
 			SolInstruction::DelPDA => {
 				let account_info_iter = &mut accounts.iter();
@@ -301,6 +342,8 @@ impl Processor {
   			let one_storage = next_account_info(account_info_iter)?;
 				let mint = next_account_info(account_info_iter)?;
 				let mint_account = next_account_info(account_info_iter)?;
+				// дивиденды приходят на кошелек владельца доли
+				let balance = payer.lamports();
 				process_withdrawal(
 					program_id,
 					payer,
@@ -308,7 +351,12 @@ impl Processor {
 					one_storage,
 					mint,
 					mint_account
-				)
+				)?;
+				emit(Event::DividendWithdrawn {
+					amount: payer.lamports().saturating_sub(balance),
+					mint: *mint.key,
+					owner: *payer.key
+				})
 			},
 			SolInstruction::Wd => {
 				let account_info_iter = &mut accounts.iter();
@@ -318,6 +366,604 @@ impl Processor {
 					payer,
 					share_storage
 				)
+			},
+			SolInstruction::SettleAuction => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let applicant = next_account_info(account_info_iter)?;
+				let applicant_account = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_settle(
+					program_id,
+					payer,
+					mint,
+					seller,
+					applicant,
+					applicant_account,
+					vault,
+					storage,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					registry,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::SetFeeConfig {data} => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_set_fee_config(
+					program_id,
+					payer,
+					fee_config,
+					rent_program,
+					system_program,
+					data
+				)
+			},
+			SolInstruction::CancelListing => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let seller_account = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_cancel(
+					program_id,
+					payer,
+					seller,
+					mint,
+					seller_account,
+					vault,
+					storage,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					registry
+				)
+			},
+			SolInstruction::UpdateListing {price} => {
+				let account_info_iter = &mut accounts.iter();
+				let seller = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				process_sell_update(
+					program_id,
+					seller,
+					mint,
+					vault,
+					storage,
+					price
+				)
+			},
+			SolInstruction::MakeOffer {price} => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let offer = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_offer_make(
+					program_id,
+					bidder,
+					mint,
+					offer,
+					rent_program,
+					system_program,
+					price
+				)
+			},
+			SolInstruction::CancelOffer => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let offer = next_account_info(account_info_iter)?;
+				process_offer_cancel(
+					program_id,
+					bidder,
+					offer
+				)
+			},
+			SolInstruction::AcceptOffer => {
+				let account_info_iter = &mut accounts.iter();
+				let owner = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let owner_account = next_account_info(account_info_iter)?;
+				let bidder = next_account_info(account_info_iter)?;
+				let bidder_account = next_account_info(account_info_iter)?;
+				let offer = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				process_offer_accept(
+					program_id,
+					owner,
+					mint,
+					owner_account,
+					bidder,
+					bidder_account,
+					offer,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::MakeCollectionOffer {key, kind, price, quantity} => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let offer = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_collection_offer_make(
+					program_id,
+					bidder,
+					offer,
+					rent_program,
+					system_program,
+					key,
+					kind,
+					price,
+					quantity
+				)
+			},
+			SolInstruction::CancelCollectionOffer => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let offer = next_account_info(account_info_iter)?;
+				process_collection_offer_cancel(
+					program_id,
+					bidder,
+					offer
+				)
+			},
+			SolInstruction::FillCollectionOffer => {
+				let account_info_iter = &mut accounts.iter();
+				let owner = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let owner_account = next_account_info(account_info_iter)?;
+				let bidder = next_account_info(account_info_iter)?;
+				let bidder_account = next_account_info(account_info_iter)?;
+				let offer = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				process_collection_offer_fill(
+					program_id,
+					owner,
+					mint,
+					owner_account,
+					bidder,
+					bidder_account,
+					offer,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::BundleCreate {sell, duration} => {
+				let account_info_iter = &mut accounts.iter();
+				let seller = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_bundle_create(
+					program_id,
+					seller,
+					storage,
+					token_program,
+					rent_program,
+					system_program,
+					account_info_iter.as_slice(),
+					sell,
+					duration
+				)
+			},
+			SolInstruction::BundleWithdrawal => {
+				let account_info_iter = &mut accounts.iter();
+				let seller = next_account_info(account_info_iter)?;
+				let buyer = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let applicant = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				process_bundle_withdrawal(
+					program_id,
+					seller,
+					buyer,
+					storage,
+					applicant,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					fee_config,
+					treasury,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::BundleSettle => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let applicant = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				process_bundle_settle(
+					program_id,
+					payer,
+					seller,
+					applicant,
+					storage,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					fee_config,
+					treasury,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::BuyNow => {
+				let account_info_iter = &mut accounts.iter();
+				let mint = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let buyer = next_account_info(account_info_iter)?;
+				let buyer_account = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let applicant = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_buy_now(
+					program_id,
+					mint,
+					seller,
+					buyer,
+					buyer_account,
+					vault,
+					storage,
+					applicant,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					registry,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::CommitSealedBid {commitment, deposit} => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let bid = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_sealed_commit(
+					program_id,
+					bidder,
+					mint,
+					vault,
+					storage,
+					bid,
+					rent_program,
+					system_program,
+					commitment,
+					deposit
+				)
+			},
+			SolInstruction::RevealSealedBid {amount, salt} => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let bid = next_account_info(account_info_iter)?;
+				process_sealed_reveal(
+					program_id,
+					bidder,
+					storage,
+					bid,
+					amount,
+					salt
+				)
+			},
+			SolInstruction::ClaimSealedBid => {
+				let account_info_iter = &mut accounts.iter();
+				let bidder = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let bid = next_account_info(account_info_iter)?;
+				process_sealed_claim(
+					program_id,
+					bidder,
+					storage,
+					bid
+				)
+			},
+			SolInstruction::SettleSealedBid => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let applicant = next_account_info(account_info_iter)?;
+				let applicant_account = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let bid = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sealed_settle(
+					program_id,
+					payer,
+					mint,
+					seller,
+					applicant,
+					applicant_account,
+					vault,
+					storage,
+					bid,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					registry,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::CreateBidHistory => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let history = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_bid_history_create(
+					program_id,
+					payer,
+					storage,
+					history,
+					rent_program,
+					system_program
+				)
+			},
+			SolInstruction::CloseBidHistory => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let history = next_account_info(account_info_iter)?;
+				process_bid_history_close(
+					program_id,
+					payer,
+					storage,
+					history
+				)
+			},
+			SolInstruction::SellDelegateCreate {sell, duration} => {
+				let account_info_iter = &mut accounts.iter();
+				let seller = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let seller_account = next_account_info(account_info_iter)?;
+				let delegate = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_delegate_create(
+					program_id,
+					seller,
+					mint,
+					seller_account,
+					delegate,
+					storage,
+					token_program,
+					rent_program,
+					system_program,
+					registry,
+					account_info_iter.as_slice(),
+					sell,
+					duration
+				)
+			},
+			SolInstruction::SellDelegateBuy => {
+				let account_info_iter = &mut accounts.iter();
+				let mint = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let buyer = next_account_info(account_info_iter)?;
+				let buyer_account = next_account_info(account_info_iter)?;
+				let seller_account = next_account_info(account_info_iter)?;
+				let delegate = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_delegate_buy(
+					program_id,
+					mint,
+					seller,
+					buyer,
+					buyer_account,
+					seller_account,
+					delegate,
+					storage,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					registry,
+					account_info_iter.as_slice()
+				)
+			},
+			SolInstruction::SellDelegateCancel => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let mint = next_account_info(account_info_iter)?;
+				let seller_account = next_account_info(account_info_iter)?;
+				let delegate = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_delegate_cancel(
+					program_id,
+					payer,
+					seller,
+					mint,
+					seller_account,
+					delegate,
+					storage,
+					token_program,
+					registry
+				)
+			},
+			SolInstruction::SellBuy {amount} => {
+				let account_info_iter = &mut accounts.iter();
+				let mint = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let buyer = next_account_info(account_info_iter)?;
+				let buyer_account = next_account_info(account_info_iter)?;
+				let vault = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let profit_id = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				let metadata_account = next_account_info(account_info_iter)?;
+				let fee_config = next_account_info(account_info_iter)?;
+				let treasury = next_account_info(account_info_iter)?;
+				let registry = next_account_info(account_info_iter)?;
+				process_sell_buy(
+					program_id,
+					mint,
+					seller,
+					buyer,
+					buyer_account,
+					vault,
+					storage,
+					profit_id,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					metadata_account,
+					fee_config,
+					treasury,
+					registry,
+					account_info_iter.as_slice(),
+					amount
+				)
+			},
+			SolInstruction::BundleCancel => {
+				let account_info_iter = &mut accounts.iter();
+				let payer = next_account_info(account_info_iter)?;
+				let seller = next_account_info(account_info_iter)?;
+				let storage = next_account_info(account_info_iter)?;
+				let token_program = next_account_info(account_info_iter)?;
+				let spl_token_program = next_account_info(account_info_iter)?;
+				let rent_program = next_account_info(account_info_iter)?;
+				let system_program = next_account_info(account_info_iter)?;
+				process_bundle_cancel(
+					program_id,
+					payer,
+					seller,
+					storage,
+					token_program,
+					spl_token_program,
+					rent_program,
+					system_program,
+					account_info_iter.as_slice()
+				)
 			}
 		}
 	}
@@ -2,5 +2,8 @@ pub mod create_mint;
 pub mod create_token_account;
 pub mod transfer_token_seed;
 pub mod transfer_token;
+pub mod transfer_token_owner;
+pub mod transfer_token_delegate;
+pub mod token_program;
 pub mod burn_token;
 pub mod mint_token;
\ No newline at end of file
@@ -0,0 +1,140 @@
+use solana_program::{
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  instruction::Instruction,
+  program::invoke,
+  program_error::ProgramError,
+  program_pack::Pack,
+  sysvar::{clock::Clock, Sysvar}
+};
+use spl_token_2022::{
+  extension::{
+    ExtensionType, StateWithExtensions,
+    transfer_fee::{TransferFeeConfig, instruction::harvest_withheld_tokens_to_mint}
+  },
+  state::{Account, Mint}
+};
+use crate::{
+  error::NftError,
+  utils::amount::{add, sub}
+};
+
+// номер NonTransferable в TLV, в этой версии крейта такого расширения еще нет
+const NON_TRANSFERABLE: u16 = 9;
+
+// токены переводятся только через spl-token или Token-2022
+pub fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+  if *token_program.key != spl_token::id() && *token_program.key != spl_token_2022::id() {
+    return Err(NftError::WrongTokenProgram.into());
+  }
+  Ok(())
+}
+
+// токен аккаунт любой из двух программ, расширения пропускаются
+pub fn unpack_account(account: &AccountInfo) -> Result<Account, ProgramError> {
+  Ok(StateWithExtensions::<Account>::unpack(&account.try_borrow_data()?)?.base)
+}
+
+pub fn unpack_mint(mint: &AccountInfo) -> Result<Mint, ProgramError> {
+  Ok(StateWithExtensions::<Mint>::unpack(&mint.try_borrow_data()?)?.base)
+}
+
+// значение расширения mint, которого нет в крейте: TLV идет после состояния, дополненного до размера аккаунта, и байта типа
+fn mint_extension(data: &[u8], extension: u16) -> Option<&[u8]> {
+  let mut offset = Account::LEN + 1;
+  while offset + 4 <= data.len() {
+    let kind = u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+    let start = offset + 4;
+    if kind == 0 || start + len > data.len() { return None; }
+    if kind == extension { return Some(&data[start..start + len]); }
+    offset = start + len;
+  }
+  None
+}
+
+// настройки комиссии за перевод, None - у mint нет комиссии
+fn transfer_fee_config(data: &[u8]) -> Result<Option<TransferFeeConfig>, ProgramError> {
+  let state = StateWithExtensions::<Mint>::unpack(data)?;
+  if !state.get_extension_types()?.contains(&ExtensionType::TransferFeeConfig) { return Ok(None); }
+  Ok(Some(*state.get_extension::<TransferFeeConfig>()?))
+}
+
+pub fn has_transfer_fee(mint: &AccountInfo) -> Result<bool, ProgramError> {
+  Ok(transfer_fee_config(&mint.try_borrow_data()?)?.is_some())
+}
+
+// проверка mint перед переводом, возвращает decimals для transfer_checked
+pub fn check_mint(mint: &AccountInfo, token_program: &AccountInfo) -> Result<u8, ProgramError> {
+  check_token_program(token_program)?;
+  if mint.owner != token_program.key { return Err(NftError::WrongTokenProgram.into()); }
+
+  let data = mint.try_borrow_data()?;
+  if mint_extension(&data, NON_TRANSFERABLE).is_some() { return Err(NftError::NonTransferableMint.into()); }
+  Ok(StateWithExtensions::<Mint>::unpack(&data)?.base.decimals)
+}
+
+// комиссия Token-2022 за перевод amount в текущую эпоху
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+  let config = match transfer_fee_config(&mint.try_borrow_data()?)? {
+    Some(config) => config,
+    None => return Ok(0)
+  };
+  let epoch = Clock::get()?.epoch;
+  Ok(config.calculate_epoch_fee(epoch, amount).ok_or(NftError::AmountOverflow)?)
+}
+
+// сколько отправить, чтобы после комиссии получатель получил amount
+pub fn gross_amount(mint: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+  let mut gross = amount;
+  for _ in 0..4 {
+    let net = gross.saturating_sub(transfer_fee(mint, gross)?);
+    if net >= amount { return Ok(gross); }
+    gross = add(gross, sub(amount, net)?)?;
+  }
+  Ok(gross)
+}
+
+// перевод с mint и decimals, без них Token-2022 не переводит токены с комиссией
+pub fn transfer_checked(
+  token_program: &AccountInfo,
+  from: &AccountInfo,
+  mint: &AccountInfo,
+  to: &AccountInfo,
+  authority: &AccountInfo,
+  amount: u64
+) -> Result<Instruction, ProgramError> {
+  let decimals = check_mint(mint, token_program)?;
+  spl_token_2022::instruction::transfer_checked(
+    token_program.key,
+    from.key,
+    mint.key,
+    to.key,
+    authority.key,
+    &[authority.key],
+    amount,
+    decimals
+  )
+}
+
+// размер нового токен аккаунта: mint с комиссией требует расширение удержанной комиссии
+pub fn account_len(mint: &AccountInfo) -> Result<usize, ProgramError> {
+  if has_transfer_fee(mint)? {
+    return Ok(ExtensionType::get_account_len::<Account>(&[ExtensionType::TransferFeeAmount]));
+  }
+  Ok(Account::LEN)
+}
+
+// аккаунт с удержанной комиссией не закрывается, перед закрытием она уходит на mint
+pub fn harvest_withheld<'a>(
+  token_program: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  account: &AccountInfo<'a>
+) -> ProgramResult {
+  if *token_program.key != spl_token_2022::id() || !has_transfer_fee(mint)? { return Ok(()); }
+
+  invoke(
+    &harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[account.key])?,
+    &[mint.clone(), account.clone(), token_program.clone()]
+  )
+}
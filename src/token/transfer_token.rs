@@ -4,7 +4,10 @@ use solana_program::{
   entrypoint::ProgramResult
 };
 use crate::{
-  token::create_token_account::process_create_token_account
+  token::{
+    create_token_account::process_create_token_account,
+    token_program::{transfer_checked, unpack_account, harvest_withheld}
+  }
 };
 
 pub fn process_transfer_token<'a>(
@@ -16,7 +19,8 @@ pub fn process_transfer_token<'a>(
   token_program: &AccountInfo<'a>,
   rent_program: &AccountInfo<'a>,
   system_program: &AccountInfo<'a>,
-  spl_token_program: &AccountInfo<'a>
+  spl_token_program: &AccountInfo<'a>,
+  amount: u64
 ) -> ProgramResult {
   
   msg!("Create ATA");
@@ -33,27 +37,33 @@ pub fn process_transfer_token<'a>(
   
   msg!("Transfer token");
   invoke(
-    &spl_token::instruction::transfer(
-      token_program.key,
-      from_token_accaunt.key,
-      to_token_accaunt.key,
-      from_token_accaunt.key,
-      &[from_token_accaunt.key],
-      1
-    ).unwrap(),
-    &[token_program.clone(), from_token_accaunt.clone(), to_token_accaunt.clone(), from_token_accaunt.clone()]
+    &transfer_checked(
+      token_program,
+      from_token_accaunt,
+      mint,
+      to_token_accaunt,
+      from_token_accaunt,
+      amount
+    )?,
+    &[token_program.clone(), from_token_accaunt.clone(), mint.clone(), to_token_accaunt.clone(), from_token_accaunt.clone()]
   )?;
 
+  // аккаунт закрываем, только когда на нем не осталось токенов
+  let from_account = unpack_account(from_token_accaunt)?;
+  if from_account.amount > 0 { return Ok(()); }
+
+  harvest_withheld(token_program, mint, from_token_accaunt)?;
+
   msg!("Close token account");
   invoke(
-    &spl_token::instruction::close_account(
+    &spl_token_2022::instruction::close_account(
       token_program.key,
       from_token_accaunt.key,
       profit_id.key,
       from_token_accaunt.key,
       &[from_token_accaunt.key]
-    ).unwrap(),
-    &[from_token_accaunt.clone(), profit_id.clone(), from_token_accaunt.clone()]
+    )?,
+    &[from_token_accaunt.clone(), profit_id.clone(), from_token_accaunt.clone(), token_program.clone()]
   )?;
 
   Ok(())
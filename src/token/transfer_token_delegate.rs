@@ -0,0 +1,55 @@
+use solana_program::{
+  msg, program::{invoke_signed},
+  account_info::AccountInfo,
+  entrypoint::ProgramResult
+};
+use crate::{
+  token::{
+    create_token_account::process_create_token_account,
+    token_program::transfer_checked
+  }
+};
+
+// перевод токена подписью PDA делегата, аккаунт владельца не закрывается
+pub fn process_transfer_token_delegate<'a>(
+  payer: &AccountInfo<'a>,
+  new_owner: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  from_token_accaunt: &AccountInfo<'a>,
+  to_token_accaunt: &AccountInfo<'a>,
+  delegate: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  signer_seeds: &[&[u8]],
+) -> ProgramResult {
+
+  msg!("Create ATA");
+  process_create_token_account(
+    payer,
+    new_owner,
+    mint,
+    to_token_accaunt,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program
+  )?;
+
+  msg!("Transfer token");
+  invoke_signed(
+    &transfer_checked(
+      token_program,
+      from_token_accaunt,
+      mint,
+      to_token_accaunt,
+      delegate,
+      1
+    )?,
+    &[token_program.clone(), from_token_accaunt.clone(), mint.clone(), to_token_accaunt.clone(), delegate.clone()],
+    &[signer_seeds],
+  )?;
+
+  Ok(())
+}
@@ -0,0 +1,52 @@
+use solana_program::{
+  msg, program::{invoke},
+  account_info::AccountInfo,
+  entrypoint::ProgramResult
+};
+use crate::{
+  token::{
+    create_token_account::process_create_token_account,
+    token_program::transfer_checked
+  }
+};
+
+// перевод токена с аккаунта пользователя его подписью, исходный аккаунт не закрывается
+pub fn process_transfer_token_owner<'a>(
+  owner: &AccountInfo<'a>,
+  new_owner: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  from_token_accaunt: &AccountInfo<'a>,
+  to_token_accaunt: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>
+) -> ProgramResult {
+
+  msg!("Create ATA");
+  process_create_token_account(
+    owner,
+    new_owner,
+    mint,
+    to_token_accaunt,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program
+  )?;
+
+  msg!("Transfer token");
+  invoke(
+    &transfer_checked(
+      token_program,
+      from_token_accaunt,
+      mint,
+      to_token_accaunt,
+      owner,
+      1
+    )?,
+    &[token_program.clone(), from_token_accaunt.clone(), mint.clone(), to_token_accaunt.clone(), owner.clone()]
+  )?;
+
+  Ok(())
+}
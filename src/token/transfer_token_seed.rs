@@ -4,7 +4,10 @@ use solana_program::{
   entrypoint::ProgramResult
 };
 use crate::{
-  token::create_token_account::process_create_token_account
+  token::{
+    create_token_account::process_create_token_account,
+    token_program::{transfer_checked, unpack_account, harvest_withheld}
+  }
 };
 
 pub fn process_transfer_token_seed<'a>(
@@ -19,6 +22,7 @@ pub fn process_transfer_token_seed<'a>(
   system_program: &AccountInfo<'a>,
   spl_token_program: &AccountInfo<'a>,
   signer_seeds: &[&[u8]],
+  amount: u64
 ) -> ProgramResult {
   
   msg!("Create ATA");
@@ -35,28 +39,34 @@ pub fn process_transfer_token_seed<'a>(
   
   msg!("Transfer token");
   invoke_signed(
-    &spl_token::instruction::transfer(
-      token_program.key,
-      from_token_accaunt.key,
-      to_token_accaunt.key,
-      from_token_accaunt.key,
-      &[from_token_accaunt.key],
-      1
-    ).unwrap(),
-    &[token_program.clone(), from_token_accaunt.clone(), to_token_accaunt.clone(), from_token_accaunt.clone()],
+    &transfer_checked(
+      token_program,
+      from_token_accaunt,
+      mint,
+      to_token_accaunt,
+      from_token_accaunt,
+      amount
+    )?,
+    &[token_program.clone(), from_token_accaunt.clone(), mint.clone(), to_token_accaunt.clone(), from_token_accaunt.clone()],
     &[signer_seeds],
   )?;
 
+  // vault закрываем, только когда он пуст
+  let from_account = unpack_account(from_token_accaunt)?;
+  if from_account.amount > 0 { return Ok(()); }
+
+  harvest_withheld(token_program, mint, from_token_accaunt)?;
+
   msg!("Close token account");
   invoke_signed(// закрываем vault аккаунт
-    &spl_token::instruction::close_account(
+    &spl_token_2022::instruction::close_account(
       token_program.key,
       from_token_accaunt.key,
       profit_id.key,
       from_token_accaunt.key,
       &[from_token_accaunt.key]
-    ).unwrap(),
-    &[from_token_accaunt.clone(), profit_id.clone(), from_token_accaunt.clone()],
+    )?,
+    &[from_token_accaunt.clone(), profit_id.clone(), from_token_accaunt.clone(), token_program.clone()],
     &[signer_seeds],
   )?;
 
@@ -0,0 +1,18 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct BidRecord {
+  pub amount: u64,
+  pub bidder: Pubkey,
+  pub time: u64, //unix timestamp
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct BidHistory {
+  pub bids: Vec<BidRecord>, //ring buffer of BID_HISTORY_MAX records, next slot - count % BID_HISTORY_MAX
+  pub count: u64, //bids recorded for the listing
+  pub listing: u64, //start_time of the listing, history resets for a new listing
+  pub payer: Pubkey, //rent payer, gets rent back on close
+  pub storage: Pubkey,
+}
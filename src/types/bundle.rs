@@ -0,0 +1,9 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+use crate::types::sell::Sell;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Bundle {
+  pub mints: Vec<Pubkey>, //tokens of the lot, vault of each is [STORAGE, program_id, mint]
+  pub sell: Sell, //sell.vault - vault of the first mint
+}
@@ -0,0 +1,49 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+// the first byte of an emitted event is the variant index, new events are only appended
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum Event {
+  ListingCreated {
+    auction: u8,
+    mint: Pubkey, //first mint for a bundle
+    price: u64,
+    seller: Pubkey,
+    storage: Pubkey,
+  },
+  BidPlaced {
+    amount: u64,
+    bidder: Pubkey,
+    storage: Pubkey,
+  },
+  BidRefunded {
+    amount: u64,
+    bidder: Pubkey,
+    storage: Pubkey, //listing storage or sealed bid account
+  },
+  SaleSettled {
+    buyer: Pubkey,
+    price: u64,
+    seller: Pubkey,
+    storage: Pubkey, //listing storage or offer account
+  },
+  LotteryBoxOpened {
+    lottery: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+  },
+  LotAdded {
+    payer: Pubkey,
+    storage: Pubkey,
+  },
+  ShareMinted {
+    mint: Pubkey,
+    owner: Pubkey,
+    total: u64, //shares minted so far
+  },
+  DividendWithdrawn {
+    amount: u64,
+    mint: Pubkey,
+    owner: Pubkey,
+  },
+}
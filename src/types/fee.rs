@@ -0,0 +1,10 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct FeeConfig {
+  pub admin: Pubkey,
+  pub cancel_penalty_bps: u16, //share of a withdrawn bet kept by the treasury
+  pub fee_bps: u16, //marketplace fee, 100 = 1%
+  pub treasury: Pubkey, //fee receiver
+}
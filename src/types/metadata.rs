@@ -26,6 +26,22 @@ pub struct Data {
     pub creators: Option<Vec<Creator>>
 }
 
+// fields stored after Data in the metadata account, missing in old accounts
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct DataTail {
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<u8>,
+    pub collection: Option<Collection>
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Collection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Creator {
     pub address: Pubkey,
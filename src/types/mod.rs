@@ -1,5 +1,12 @@
 pub mod metadata;
 pub mod lottery;
 pub mod sell;
+pub mod fee;
+pub mod offer;
+pub mod bundle;
+pub mod sealed_bid;
+pub mod bid_history;
+pub mod registry;
+pub mod event;
 pub mod nft_lottery;
 pub mod share;
\ No newline at end of file
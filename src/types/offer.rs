@@ -0,0 +1,18 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Offer {
+  pub bidder: Pubkey,
+  pub mint: Pubkey,
+  pub price: u64, //lamports held on the offer account above rent
+}
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CollectionOffer {
+  pub bidder: Pubkey,
+  pub key: Pubkey, //verified creator or verified collection required in the token metadata
+  pub kind: u8, //0 - key is a verified creator, 1 - key is a verified collection
+  pub price: u64, //per token
+  pub quantity: u64, //tokens left to buy
+}
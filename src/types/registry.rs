@@ -0,0 +1,10 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ListingRegistry {
+  pub auction: u8, //mode of the active listing, as in Sell.auction
+  pub kind: u8, //0 - vault listing, 1 - bundle, 2 - delegate listing
+  pub mint: Pubkey,
+  pub storage: Pubkey, //storage of the active listing
+}
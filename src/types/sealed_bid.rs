@@ -0,0 +1,12 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct SealedBid {
+  pub amount: u64, //revealed bid, 0 - not revealed
+  pub bidder: Pubkey,
+  pub commitment: [u8; 32], //hashv(storage, bidder, listing start_time le bytes, amount le bytes, salt)
+  pub deposit: u64, //lamports held on the bid account above rent
+  pub listing: u64, //start_time of the listing the bid was made for
+  pub storage: Pubkey,
+}
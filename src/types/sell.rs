@@ -3,11 +3,40 @@ use solana_program::pubkey::Pubkey;
 
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct Sell {
+  pub version: u8, //storage layout, SELL_VERSION; 0 - decoded from a legacy storage
+  pub allowed_buyer: Option<Pubkey>, //private sale, None - anyone
   pub applicant: Pubkey, //current payer
+  pub auction: u8, //0 - fixed price, 1 - auction, 2 - dutch auction, 3 - sealed-bid auction
+  pub bets: u64,
+  pub bid_history: bool, //bid history account is open, every bet must pass it
+  pub buy_now_price: u64, //auction ends at once when a buyer pays it, 0 - disabled
+  pub decay_step: u64, //dutch price drops every decay_step seconds, 0 - linear
+  pub end_time: u64, //unix timestamp, auction closes for bets or fixed price listing expires, 0 - no expiry
+  pub extend_time: u64, //seconds added to end_time by a late bet
+  pub extend_window: u64, //last seconds before end_time when a bet extends the auction
+  pub min_increment_bps: u16, //next bet step, 100 = 1%
+  pub payment_mint: Option<Pubkey>, //None - SOL
+  pub price: u64, //fixed price: per unit, sealed-bid: highest revealed bid
+  pub price_rule: u8, //sealed-bid winner pays: 0 - own bid, 1 - second price
+  pub quantity: u64, //units left in vault, auctions sell 1
+  pub reserve_price: u64, //lowest winning bet (dutch floor price), 0 - no reserve
+  pub reveal_time: u64, //sealed-bid reveal phase after end_time, seconds
+  pub second_price: u64, //sealed-bid: second highest revealed bid or reserve
+  pub seller: Pubkey,
+  pub start_price: u64,
+  pub start_time: u64, //unix timestamp
+  pub vault: Pubkey, //vault
+  pub withdraw_policy: u8, //top bidder withdrawal: 0 - never, 1 - before end_time with penalty, 2 - after end_time while unsettled
+}
+
+// storage layout before versioning, old listings are read and written back in it
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct LegacySell {
+  pub applicant: Pubkey,
   pub auction: u8,
   pub bets: u64,
   pub price: u64,
   pub seller: Pubkey,
   pub start_price: u64,
-  pub vault: Pubkey, //vault
-}
\ No newline at end of file
+  pub vault: Pubkey,
+}
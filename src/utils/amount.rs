@@ -0,0 +1,111 @@
+use solana_program::{
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program_error::ProgramError
+};
+use crate::error::NftError;
+
+// доля суммы в базисных пунктах (10000 = 100%), округление вниз
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64, ProgramError> {
+  let part = (amount as u128)
+    .checked_mul(bps as u128)
+    .ok_or(NftError::AmountOverflow)?
+    / 10000;
+  Ok(u64::try_from(part).map_err(|_| NftError::AmountOverflow)?)
+}
+
+// amount * num / den через u128
+pub fn mul_div(amount: u64, num: u64, den: u64) -> Result<u64, ProgramError> {
+  if den == 0 { return Err(NftError::AmountOverflow.into()); }
+  let part = (amount as u128)
+    .checked_mul(num as u128)
+    .ok_or(NftError::AmountOverflow)?
+    / den as u128;
+  Ok(u64::try_from(part).map_err(|_| NftError::AmountOverflow)?)
+}
+
+pub fn add(a: u64, b: u64) -> Result<u64, ProgramError> {
+  Ok(a.checked_add(b).ok_or(NftError::AmountOverflow)?)
+}
+
+pub fn mul(a: u64, b: u64) -> Result<u64, ProgramError> {
+  Ok(a.checked_mul(b).ok_or(NftError::AmountOverflow)?)
+}
+
+pub fn sub(a: u64, b: u64) -> Result<u64, ProgramError> {
+  Ok(a.checked_sub(b).ok_or(NftError::AmountUnderflow)?)
+}
+
+// перевод лампортов с аккаунта программы (storage, pda)
+pub fn move_lamports(
+  from: &AccountInfo,
+  to: &AccountInfo,
+  amount: u64
+) -> ProgramResult {
+  let from_lamports = sub(from.lamports(), amount)?;
+  let to_lamports = add(to.lamports(), amount)?;
+  **from.lamports.borrow_mut() = from_lamports;
+  **to.lamports.borrow_mut() = to_lamports;
+  Ok(())
+}
+
+// закрытие аккаунта программы: все лампорты получателю
+pub fn close_lamports(
+  from: &AccountInfo,
+  to: &AccountInfo
+) -> ProgramResult {
+  move_lamports(from, to, from.lamports())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn overflow() -> ProgramError { NftError::AmountOverflow.into() }
+
+  #[test]
+  fn bps_of_rounds_down() {
+    assert_eq!(bps_of(999, 250).unwrap(), 24);
+    assert_eq!(bps_of(1, 9999).unwrap(), 0);
+    assert_eq!(bps_of(10000, 1).unwrap(), 1);
+  }
+
+  #[test]
+  fn bps_of_full_share_is_amount() {
+    for amount in [0, 1, 12345, u64::MAX] {
+      assert_eq!(bps_of(amount, 10000).unwrap(), amount);
+    }
+  }
+
+  #[test]
+  fn bps_of_overflow() {
+    assert_eq!(bps_of(u64::MAX, 10001).unwrap_err(), overflow());
+  }
+
+  #[test]
+  fn mul_div_uses_wide_product() {
+    assert_eq!(mul_div(u64::MAX, 3, 4).unwrap(), u64::MAX / 4 * 3 + 2);
+    assert_eq!(mul_div(10, 1, 3).unwrap(), 3);
+  }
+
+  #[test]
+  fn mul_div_overflow() {
+    assert_eq!(mul_div(u64::MAX, 2, 1).unwrap_err(), overflow());
+    assert_eq!(mul_div(1, 1, 0).unwrap_err(), overflow());
+  }
+
+  #[test]
+  fn add_mul_overflow() {
+    assert_eq!(add(u64::MAX, 1).unwrap_err(), overflow());
+    assert_eq!(mul(u64::MAX, 2).unwrap_err(), overflow());
+    assert_eq!(add(2, 3).unwrap(), 5);
+    assert_eq!(mul(2, 3).unwrap(), 6);
+  }
+
+  #[test]
+  fn sub_underflow() {
+    assert_eq!(sub(5, 3).unwrap(), 2);
+    assert_eq!(sub(3, 3).unwrap(), 0);
+    assert_eq!(sub(3, 5).unwrap_err(), NftError::AmountUnderflow.into());
+  }
+}
@@ -0,0 +1,166 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  msg,
+  account_info::{next_account_info, AccountInfo},
+  entrypoint::ProgramResult,
+  program::invoke_signed,
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{rent::Rent, Sysvar}
+};
+use crate::{
+  BID_HISTORY, BID_HISTORY_MAX,
+  types::{
+    sell::Sell,
+    bundle::Bundle,
+    bid_history::{BidHistory, BidRecord}
+  },
+  utils::{
+    sell_storage::{load_sell, save_sell},
+    bundle::check_bundle_pda,
+    amount::{add, close_lamports}
+  }
+};
+use std::slice::Iter;
+
+fn check_bid_history_pda(
+  program_id: &Pubkey,
+  storage: &Pubkey,
+  history: &AccountInfo
+) -> Result<u8, ProgramError> {
+  let (calc_history, history_seed) = Pubkey::find_program_address(
+    &[BID_HISTORY.as_bytes(), program_id.as_ref(), storage.as_ref()], &program_id
+  );
+  if calc_history != *history.key { return Err(ProgramError::InvalidArgument); }
+  Ok(history_seed)
+}
+
+// настройки аукциона из storage листинга или лота
+fn listing_settings(program_id: &Pubkey, storage: &AccountInfo) -> Result<Sell, ProgramError> {
+  if let Ok(bundle) = Bundle::try_from_slice(&storage.data.borrow()) {
+    if check_bundle_pda(program_id, &bundle, storage).is_ok() { return Ok(bundle.sell); }
+  }
+  load_sell(storage)
+}
+
+// после создания истории ставки без ее аккаунта не принимаются
+fn enable_bid_history(program_id: &Pubkey, storage: &AccountInfo) -> ProgramResult {
+  if let Ok(mut bundle) = Bundle::try_from_slice(&storage.data.borrow()) {
+    if check_bundle_pda(program_id, &bundle, storage).is_ok() {
+      bundle.sell.bid_history = true;
+      bundle.serialize(&mut &mut storage.data.borrow_mut()[..])?;
+      return Ok(());
+    }
+  }
+  let mut settings = load_sell(storage)?;
+  settings.bid_history = true;
+  save_sell(&settings, storage)
+}
+
+// история ставок аукциона, размер фиксирован на BID_HISTORY_MAX записей
+pub fn process_bid_history_create<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  history: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+  if !history.data_is_empty() { return Err(ProgramError::AccountAlreadyInitialized); }
+
+  let settings = listing_settings(program_id, storage)?;
+  if settings.auction != 1 { return Err(ProgramError::InvalidArgument); }
+
+  let history_seed = check_bid_history_pda(program_id, storage.key, history)?;
+  let history_signer_seeds = &[BID_HISTORY.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[history_seed]];
+
+  let data = BidHistory {
+    bids: vec![BidRecord::default(); BID_HISTORY_MAX],
+    count: 0,
+    listing: settings.start_time,
+    payer: *payer.key,
+    storage: *storage.key
+  };
+
+  msg!("Create bid history");
+  let rent = &Rent::from_account_info(rent_program)?;
+  let space = data.try_to_vec()?.len();
+  invoke_signed(
+    &system_instruction::create_account(
+      payer.key,
+      history.key,
+      rent.minimum_balance(space),
+      space as u64,
+      &program_id
+    ),
+    &[payer.clone(), history.clone(), system_program.clone()],
+    &[history_signer_seeds],
+  )?;
+  let _ = data.serialize(&mut &mut history.data.borrow_mut()[..]);
+
+  enable_bid_history(program_id, storage)
+}
+
+// запись ставки: без истории аккаунт не передается, после ее создания он обязателен
+// старые записи перезаписываются по кругу
+pub fn record_bid<'a, 'b>(
+  program_id: &Pubkey,
+  settings: &Sell,
+  storage: &AccountInfo<'a>,
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>,
+  now: u64
+) -> ProgramResult {
+  if !settings.bid_history { return Ok(()); }
+
+  let history = next_account_info(account_info_iter)?;
+  check_bid_history_pda(program_id, storage.key, history)?;
+
+  let mut data = BidHistory::try_from_slice(&history.data.borrow())?;
+  if data.storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+
+  // новый листинг на том же storage начинает историю заново
+  if data.listing != settings.start_time {
+    data.bids = vec![BidRecord::default(); BID_HISTORY_MAX];
+    data.count = 0;
+    data.listing = settings.start_time;
+  }
+
+  let slot = (data.count % BID_HISTORY_MAX as u64) as usize;
+  data.bids[slot] = BidRecord {
+    amount: settings.price,
+    bidder: settings.applicant,
+    time: now
+  };
+  data.count = add(data.count, 1)?;
+
+  let _ = data.serialize(&mut &mut history.data.borrow_mut()[..]);
+  Ok(())
+}
+
+// закрытие истории после окончания листинга, аренда возвращается плательщику
+pub fn process_bid_history_close<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  history: &AccountInfo<'a>
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let data = BidHistory::try_from_slice(&history.data.borrow())?;
+  if data.payer != *payer.key { return Err(ProgramError::InvalidArgument); }
+  if data.storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+  check_bid_history_pda(program_id, storage.key, history)?;
+
+  // пока листинг открыт, историю не закрываем
+  if !storage.data_is_empty() {
+    let settings = listing_settings(program_id, storage)?;
+    if settings.start_time == data.listing { return Err(ProgramError::InvalidArgument); }
+  }
+
+  msg!("Close bid history");
+  close_lamports(history, payer)?;
+
+  Ok(())
+}
@@ -0,0 +1,633 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  msg,
+  account_info::{next_account_info, AccountInfo},
+  entrypoint::ProgramResult,
+  program::{invoke, invoke_signed},
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{clock::Clock, rent::Rent, Sysvar}
+};
+use crate::{
+  STORAGE, SHARE_SEED, ESCROW, BUNDLE, BUNDLE_MAX,
+  error::NftError,
+  types::{
+    bundle::Bundle,
+    sell::Sell,
+    fee::FeeConfig,
+    event::Event
+  },
+  token::{
+    transfer_token_seed::process_transfer_token_seed,
+    token_program::{check_mint, account_len, unpack_account}
+  },
+  utils::{
+    sell_withdrawal::{dutch_price, sell_expired, check_buyer, pay_sale, withdraw_penalty_bps, cancel_bet, place_bet},
+    sell_init::init_sell,
+    sell_buy::fill_vault,
+    bid_history::record_bid,
+    payment::{Payment, load_payment, check_payment_mint, create_escrow, pay_out, close_escrow},
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::{open_registry, close_registry},
+    event::emit,
+    amount::{bps_of, mul_div, add, sub, close_lamports}
+  }
+};
+use std::slice::Iter;
+
+// аккаунты одного токена лота: mint, vault, токен аккаунт получателя, metadata, реестр листинга
+struct BundleItem<'a, 'b> {
+  mint: &'b AccountInfo<'a>,
+  vault: &'b AccountInfo<'a>,
+  vault_seed: u8,
+  recipient: &'b AccountInfo<'a>,
+  metadata: &'b AccountInfo<'a>,
+  registry: &'b AccountInfo<'a>
+}
+
+pub fn check_bundle_pda(
+  program_id: &Pubkey,
+  bundle: &Bundle,
+  storage: &AccountInfo
+) -> ProgramResult {
+  let (calc_storage, _) = Pubkey::find_program_address(
+    &[BUNDLE.as_bytes(), program_id.as_ref(), bundle.sell.vault.as_ref()], &program_id
+  );
+  if calc_storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+  Ok(())
+}
+
+// токены лота передаются в том же порядке, что и при создании
+fn read_items<'a, 'b>(
+  program_id: &Pubkey,
+  bundle: &Bundle,
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>
+) -> Result<Vec<BundleItem<'a, 'b>>, ProgramError> {
+  let mut items = Vec::new();
+  for mint_key in bundle.mints.iter() {
+    let mint = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    let metadata = next_account_info(account_info_iter)?;
+    let registry = next_account_info(account_info_iter)?;
+
+    if *mint.key != *mint_key { return Err(ProgramError::InvalidArgument); }
+
+    let (calc_vault, vault_seed) = Pubkey::find_program_address(
+      &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref()], &program_id
+    );
+    if calc_vault != *vault.key { return Err(ProgramError::InvalidArgument); }
+
+    items.push(BundleItem { mint, vault, vault_seed, recipient, metadata, registry });
+  }
+  Ok(items)
+}
+
+// все токены лота одному владельцу, vault закрываются на close_dest
+fn transfer_items<'a>(
+  program_id: &Pubkey,
+  items: &[BundleItem<'a, '_>],
+  payer: &AccountInfo<'a>,
+  new_owner: &AccountInfo<'a>,
+  close_dest: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>
+) -> ProgramResult {
+  for item in items.iter() {
+    let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), item.mint.key.as_ref(), &[item.vault_seed]];
+    process_transfer_token_seed(
+      payer,
+      new_owner,
+      item.mint,
+      item.vault,
+      item.recipient,
+      close_dest,
+      token_program,
+      rent_program,
+      system_program,
+      spl_token_program,
+      vault_signer_seeds,
+      1
+    )?;
+
+    let spl_token_account = unpack_account(item.recipient)?;
+    if spl_token_account.owner != *new_owner.key { return Err(ProgramError::InvalidArgument); }
+  }
+  Ok(())
+}
+
+// лот закрыт, записи реестра по всем токенам удаляются
+fn close_item_registries<'a>(
+  program_id: &Pubkey,
+  items: &[BundleItem<'a, '_>],
+  storage: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>
+) -> ProgramResult {
+  for item in items.iter() {
+    close_registry(program_id, item.mint.key, storage.key, item.registry, seller)?;
+  }
+  Ok(())
+}
+
+// роялти считаются с доли цены каждого токена, создатели идут подряд после всех токенов лота
+fn bundle_royalties<'a, 'b>(
+  settings: &Sell,
+  items: &[BundleItem<'a, 'b>],
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>,
+  price: u64
+) -> Result<Vec<(&'b AccountInfo<'a>, u64)>, ProgramError> {
+  let item_price = mul_div(price, 1, items.len() as u64)?;
+  let mut payouts = Vec::new();
+  for item in items.iter() {
+    payouts.extend(royalties(&settings.payment_mint, item.mint, item.metadata, account_info_iter, item_price)?);
+  }
+  Ok(payouts)
+}
+
+// лот из нескольких токенов: vault на каждый mint, один storage на весь лот
+pub fn process_bundle_create<'a>(
+  program_id: &Pubkey,
+  seller: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>],
+  mut sell: Sell,
+  duration: u64
+) -> ProgramResult {
+  if !seller.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+  if !storage.data_is_empty() { return Err(ProgramError::AccountAlreadyInitialized); }
+  init_sell(&mut sell, duration)?;
+  // закрытого аукциона и выкупа для лотов нет
+  if sell.auction == 3 || sell.buy_now_price != 0 { return Err(NftError::WrongSellSettings.into()); }
+  // лот продается целиком по одному токену каждого mint
+  sell.quantity = 1;
+
+  // для SPL листинга первым идет mint оплаты, для аукциона еще escrow
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment_mint = check_payment_mint(&sell, account_info_iter)?;
+  create_escrow(
+    program_id,
+    &sell,
+    seller,
+    storage,
+    payment_mint,
+    token_program,
+    rent_program,
+    system_program,
+    account_info_iter
+  )?;
+  let item_accounts = account_info_iter.as_slice();
+
+  // на каждый токен: mint, токен аккаунт продавца, vault, реестр листинга
+  if item_accounts.len() % 4 != 0 { return Err(ProgramError::NotEnoughAccountKeys); }
+  let count = item_accounts.len() / 4;
+  if count == 0 || count > BUNDLE_MAX { return Err(NftError::WrongSellSettings.into()); }
+
+  let rent = &Rent::from_account_info(rent_program)?;
+  let mut mints = Vec::new();
+  let account_info_iter = &mut item_accounts.iter();
+  for _ in 0..count {
+    let mint = next_account_info(account_info_iter)?;
+    let seller_account = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let registry = next_account_info(account_info_iter)?;
+
+    let (calc_vault, vault_seed) = Pubkey::find_program_address(
+      &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref()], &program_id
+    );
+    if calc_vault != *vault.key { return Err(ProgramError::InvalidArgument); }
+    let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
+
+    check_mint(mint, token_program)?;
+
+    msg!("Create vault");
+    let vault_len = account_len(mint)?;
+    invoke_signed(
+      &system_instruction::create_account(
+        seller.key,
+        vault.key,
+        rent.minimum_balance(vault_len),
+        vault_len as u64,
+        token_program.key
+      ),
+      &[seller.clone(), vault.clone(), system_program.clone()],
+      &[vault_signer_seeds],
+    )?;
+
+    invoke(
+      &spl_token_2022::instruction::initialize_account(
+        token_program.key,
+        vault.key,
+        mint.key,
+        vault.key
+      )?,
+      &[vault.clone(), mint.clone(), vault.clone(), rent_program.clone(), token_program.clone()]
+    )?;
+
+    fill_vault(seller, seller_account, mint, vault, token_program, 1)?;
+
+    open_registry(
+      program_id,
+      seller,
+      mint.key,
+      storage.key,
+      registry,
+      rent_program,
+      system_program,
+      sell.auction,
+      1
+    )?;
+
+    mints.push(*mint.key);
+  }
+
+  let (first_vault, _) = Pubkey::find_program_address(
+    &[STORAGE.as_bytes(), program_id.as_ref(), mints[0].as_ref()], &program_id
+  );
+
+  sell.seller = *seller.key;
+  sell.applicant = *seller.key;
+  sell.bets = 0;
+  sell.vault = first_vault;
+
+  let (calc_storage, storage_seed) = Pubkey::find_program_address(
+    &[BUNDLE.as_bytes(), program_id.as_ref(), first_vault.as_ref()], &program_id
+  );
+  if calc_storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+  let storage_signer_seeds = &[BUNDLE.as_bytes(), program_id.as_ref(), first_vault.as_ref(), &[storage_seed]];
+
+  let data = Bundle { mints, sell };
+
+  msg!("Create bundle storage");
+  let space = data.try_to_vec()?.len();
+  invoke_signed(
+    &system_instruction::create_account(
+      seller.key,
+      storage.key,
+      rent.minimum_balance(space),
+      space as u64,
+      &program_id
+    ),
+    &[seller.clone(), storage.clone(), system_program.clone()],
+    &[storage_signer_seeds],
+  )?;
+  let _ = data.serialize(&mut &mut storage.data.borrow_mut()[..]);
+
+  emit(Event::ListingCreated {
+    auction: data.sell.auction,
+    mint: data.mints[0],
+    price: data.sell.price,
+    seller: *seller.key,
+    storage: *storage.key
+  })
+}
+
+// покупка лота, вывод продавцом и ставки на аукционе
+// после счетов оплаты идут токены лота (mint, vault, токен аккаунт получателя, metadata, реестр), затем создатели для роялти
+pub fn process_bundle_withdrawal<'a>(
+  program_id: &Pubkey,
+  seller: &AccountInfo<'a>,
+  buyer: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !buyer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let mut bundle = Bundle::try_from_slice(&storage.data.borrow())?;
+  if bundle.sell.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  check_bundle_pda(program_id, &bundle, storage)?;
+
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &bundle.sell, storage, buyer, seller, applicant, treasury, account_info_iter)?;
+  let escrow_signer_seeds = &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[payment.escrow_seed]];
+
+  let now = Clock::get()?.unix_timestamp as u64;
+
+  if bundle.sell.auction == 0 || bundle.sell.auction == 2 { // SELL BUNDLE
+    let price = if bundle.sell.auction == 2 { dutch_price(&bundle.sell, now)? } else { bundle.sell.price };
+
+    check_buyer(&bundle.sell, buyer.key, now)?;
+
+    let items = read_items(program_id, &bundle, account_info_iter)?;
+
+    if bundle.sell.seller != *buyer.key {
+      let payouts = bundle_royalties(&bundle.sell, &items, account_info_iter, price)?;
+      pay_sale(
+        &config,
+        price,
+        buyer,
+        &payment,
+        &payouts,
+        token_program,
+        system_program
+      )?;
+    }
+
+    msg!("Transfer tokens");
+    transfer_items(
+      program_id,
+      &items,
+      buyer,
+      buyer,
+      profit_id,
+      token_program,
+      spl_token_program,
+      rent_program,
+      system_program
+    )?;
+
+    if bundle.sell.seller != *buyer.key {
+      emit(Event::SaleSettled {
+        buyer: *buyer.key,
+        price,
+        seller: bundle.sell.seller,
+        storage: *storage.key
+      })?;
+    }
+
+    close_item_registries(program_id, &items, storage, seller)?;
+
+    close_lamports(storage, profit_id)?;
+
+    msg!("Process bundle withdrawal done");
+    Ok(())
+  }
+  else { //AUCTION
+    if bundle.sell.auction != 1 { return Err(ProgramError::InvalidArgument); }
+    if bundle.sell.applicant != *applicant.key { return Err(ProgramError::InvalidArgument); }
+
+    if bundle.sell.seller == *buyer.key {// запрос владельца лота
+      if bundle.sell.applicant != bundle.sell.seller && now < bundle.sell.end_time {
+        return Err(NftError::AuctionNotEnded.into());
+      }
+
+      settle_bundle(
+        program_id,
+        &bundle,
+        buyer,
+        seller,
+        applicant,
+        storage,
+        profit_id,
+        token_program,
+        spl_token_program,
+        rent_program,
+        system_program,
+        &config,
+        &payment,
+        escrow_signer_seeds,
+        account_info_iter
+      )
+    }
+    else {// значит запрос претендента
+      if bundle.sell.applicant == *buyer.key {
+        // отзыв ставки по правилу листинга
+        let penalty_bps = withdraw_penalty_bps(&bundle.sell, &config, now)?;
+
+        msg!("Cancel bet");
+        cancel_bet(&mut bundle.sell, storage, penalty_bps, &payment, token_program, escrow_signer_seeds)?;
+
+        let _ = bundle.serialize(&mut &mut storage.data.borrow_mut()[..]);
+
+        msg!("Bet withdrawn");
+        Ok(())
+      }
+      else {
+        // после окончания доступно только закрытие аукциона
+        if now >= bundle.sell.end_time { return Err(NftError::AuctionEnded.into()); }
+
+        msg!("Start new bet");
+        place_bet(
+          &mut bundle.sell,
+          storage,
+          buyer,
+          &payment,
+          now,
+          token_program,
+          system_program,
+          escrow_signer_seeds
+        )?;
+
+        // после счетов оплаты идет аккаунт истории ставок, если она создана
+        record_bid(program_id, &bundle.sell, storage, account_info_iter, now)?;
+
+        let _ = bundle.serialize(&mut &mut storage.data.borrow_mut()[..]);
+
+        msg!("New bet done");
+        Ok(())
+      }
+    }
+  }
+}
+
+// закрытие аукциона лота после окончания, вызвать может кто угодно
+pub fn process_bundle_settle<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let bundle = Bundle::try_from_slice(&storage.data.borrow())?;
+  if bundle.sell.auction != 1 { return Err(ProgramError::InvalidArgument); }
+  check_bundle_pda(program_id, &bundle, storage)?;
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  if now < bundle.sell.end_time { return Err(NftError::AuctionNotEnded.into()); }
+
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &bundle.sell, storage, payer, seller, applicant, treasury, account_info_iter)?;
+  let escrow_signer_seeds = &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[payment.escrow_seed]];
+
+  settle_bundle(
+    program_id,
+    &bundle,
+    payer,
+    seller,
+    applicant,
+    storage,
+    profit_id,
+    token_program,
+    spl_token_program,
+    rent_program,
+    system_program,
+    &config,
+    &payment,
+    escrow_signer_seeds,
+    account_info_iter
+  )
+}
+
+// все токены лота победителю (или продавцу, если ставок не было или не достигнут резерв), storage закрываем
+fn settle_bundle<'a, 'b>(
+  program_id: &Pubkey,
+  bundle: &Bundle,
+  payer: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  config: &FeeConfig,
+  payment: &Payment<'a, '_>,
+  escrow_signer_seeds: &[&[u8]],
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>
+) -> ProgramResult {
+  let settings = &bundle.sell;
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if settings.applicant != *applicant.key { return Err(ProgramError::InvalidArgument); }
+
+  let has_bets = settings.applicant != settings.seller;
+  let reserve_met = settings.price >= settings.reserve_price;
+  let winner = if has_bets && reserve_met { applicant } else { seller };
+
+  let items = read_items(program_id, bundle, account_info_iter)?;
+
+  msg!("Transfer tokens to auction winner and close auction");
+  transfer_items(
+    program_id,
+    &items,
+    payer,
+    winner,
+    profit_id,
+    token_program,
+    spl_token_program,
+    rent_program,
+    system_program
+  )?;
+
+  if has_bets && reserve_met {
+    msg!("Transfer royalties");
+    let mut royalty: u64 = 0;
+    for (creator, amount) in bundle_royalties(settings, &items, account_info_iter, settings.price)? {
+      pay_out(payment.mint, payment.escrow, creator, amount, token_program, escrow_signer_seeds)?;
+      royalty = add(royalty, amount)?;
+    }
+
+    msg!("Transfer fee");
+    let fee_amount = bps_of(settings.price, config.fee_bps)?;
+    pay_out(payment.mint, payment.escrow, payment.treasury, fee_amount, token_program, escrow_signer_seeds)?;
+
+    let am = sub(sub(settings.price, fee_amount)?, royalty)?;
+    pay_out(payment.mint, payment.escrow, payment.seller, am, token_program, escrow_signer_seeds)?;
+
+    emit(Event::SaleSettled {
+      buyer: settings.applicant,
+      price: settings.price,
+      seller: settings.seller,
+      storage: *storage.key
+    })?;
+  }
+  else if has_bets {
+    msg!("Reserve price not met, refund bet");
+    pay_out(payment.mint, payment.escrow, payment.applicant, settings.price, token_program, escrow_signer_seeds)?;
+
+    emit(Event::BidRefunded {
+      amount: settings.price,
+      bidder: settings.applicant,
+      storage: *storage.key
+    })?;
+  }
+
+  close_escrow(payment, seller, token_program, escrow_signer_seeds)?;
+
+  close_item_registries(program_id, &items, storage, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, profit_id)?;
+
+  msg!("Bundle auction done");
+  Ok(())
+}
+
+// снятие лота с продажи: токены возвращаются продавцу, аренда vault и storage тоже
+// просроченный лот по фиксированной цене может закрыть кто угодно
+// после системных аккаунтов идут токены лота (mint, vault, токен аккаунт продавца, metadata, реестр)
+pub fn process_bundle_cancel<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let bundle = Bundle::try_from_slice(&storage.data.borrow())?;
+  if bundle.sell.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  check_bundle_pda(program_id, &bundle, storage)?;
+  // аукцион закрывается через BundleSettle
+  if bundle.sell.auction == 1 { return Err(ProgramError::InvalidArgument); }
+
+  if *payer.key != bundle.sell.seller {
+    let now = Clock::get()?.unix_timestamp as u64;
+    if !sell_expired(&bundle.sell, now) { return Err(NftError::ListingNotExpired.into()); }
+  }
+
+  let items = read_items(program_id, &bundle, &mut remaining_accounts.iter())?;
+
+  msg!("Return tokens to seller");
+  transfer_items(
+    program_id,
+    &items,
+    payer,
+    seller,
+    seller,
+    token_program,
+    spl_token_program,
+    rent_program,
+    system_program
+  )?;
+
+  close_item_registries(program_id, &items, storage, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, seller)?;
+
+  msg!("Bundle cancelled");
+  Ok(())
+}
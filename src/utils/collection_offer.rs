@@ -0,0 +1,194 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program::invoke_signed,
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{rent::Rent, Sysvar}
+};
+use crate::{
+  COLLECTION_OFFER,
+  error::NftError,
+  types::{
+    offer::CollectionOffer,
+    event::Event
+  },
+  token::{
+    transfer_token_owner::process_transfer_token_owner,
+    token_program::unpack_account
+  },
+  utils::{
+    fee_config::load_fee_config,
+    event::emit,
+    royalty::{royalties, read_metadata, read_collection},
+    amount::{bps_of, mul, add, sub, move_lamports, close_lamports}
+  }
+};
+
+fn check_collection_offer_pda(
+  program_id: &Pubkey,
+  key: &Pubkey,
+  bidder: &Pubkey,
+  offer: &AccountInfo
+) -> Result<u8, ProgramError> {
+  let (calc_offer, offer_seed) = Pubkey::find_program_address(
+    &[COLLECTION_OFFER.as_bytes(), program_id.as_ref(), key.as_ref(), bidder.as_ref()], &program_id
+  );
+  if calc_offer != *offer.key { return Err(ProgramError::InvalidArgument); }
+  Ok(offer_seed)
+}
+
+// предложение на любые токены коллекции (по верифицированному создателю или коллекции), SOL за все количество лежат на аккаунте
+pub fn process_collection_offer_make<'a>(
+  program_id: &Pubkey,
+  bidder: &AccountInfo<'a>,
+  offer: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  key: Pubkey,
+  kind: u8,
+  price: u64,
+  quantity: u64
+) -> ProgramResult {
+  if !bidder.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+  if price == 0 || quantity == 0 || kind > 1 { return Err(NftError::WrongSellSettings.into()); }
+  if !offer.data_is_empty() { return Err(ProgramError::AccountAlreadyInitialized); }
+
+  let offer_seed = check_collection_offer_pda(program_id, &key, bidder.key, offer)?;
+  let offer_signer_seeds = &[COLLECTION_OFFER.as_bytes(), program_id.as_ref(), key.as_ref(), bidder.key.as_ref(), &[offer_seed]];
+
+  let data = CollectionOffer {
+    bidder: *bidder.key,
+    key,
+    kind,
+    price,
+    quantity
+  };
+
+  msg!("Create collection offer");
+  let rent = &Rent::from_account_info(rent_program)?;
+  let space = data.try_to_vec()?.len();
+  let lamports = add(rent.minimum_balance(space), mul(price, quantity)?)?;
+  invoke_signed(
+    &system_instruction::create_account(
+      bidder.key,
+      offer.key,
+      lamports,
+      space as u64,
+      &program_id
+    ),
+    &[bidder.clone(), offer.clone(), system_program.clone()],
+    &[offer_signer_seeds],
+  )?;
+  let _ = data.serialize(&mut &mut offer.data.borrow_mut()[..]);
+
+  Ok(())
+}
+
+// отзыв предложения, остаток SOL возвращается покупателю
+pub fn process_collection_offer_cancel<'a>(
+  program_id: &Pubkey,
+  bidder: &AccountInfo<'a>,
+  offer: &AccountInfo<'a>
+) -> ProgramResult {
+  if !bidder.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let data = CollectionOffer::try_from_slice(&offer.data.borrow())?;
+  if data.bidder != *bidder.key { return Err(ProgramError::InvalidArgument); }
+  check_collection_offer_pda(program_id, &data.key, bidder.key, offer)?;
+
+  msg!("Close collection offer");
+  close_lamports(offer, bidder)?;
+
+  Ok(())
+}
+
+// держатель токена коллекции продает его по предложению
+pub fn process_collection_offer_fill<'a>(
+  program_id: &Pubkey,
+  owner: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  owner_account: &AccountInfo<'a>,
+  bidder: &AccountInfo<'a>,
+  bidder_account: &AccountInfo<'a>,
+  offer: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  creator_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !owner.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let mut data = CollectionOffer::try_from_slice(&offer.data.borrow())?;
+  if data.bidder != *bidder.key { return Err(ProgramError::InvalidArgument); }
+  check_collection_offer_pda(program_id, &data.key, bidder.key, offer)?;
+
+  // токен должен принадлежать коллекции: ключ из предложения верифицирован в metadata
+  let in_collection = match data.kind {
+    0 => read_metadata(mint, metadata_account)?
+      .creators
+      .unwrap_or_default()
+      .iter()
+      .any(|c| c.verified && c.address == data.key),
+    _ => read_collection(mint, metadata_account)?
+      .map_or(false, |c| c.verified && c.key == data.key)
+  };
+  if !in_collection { return Err(NftError::NotInCollection.into()); }
+
+  msg!("Transfer token to bidder");
+  process_transfer_token_owner(
+    owner,
+    bidder,
+    mint,
+    owner_account,
+    bidder_account,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program
+  )?;
+
+  let spl_token_account = unpack_account(bidder_account)?;
+  if spl_token_account.owner != data.bidder { return Err(ProgramError::InvalidArgument); }
+
+  msg!("Transfer fee");
+  let fee_amount = bps_of(data.price, config.fee_bps)?;
+  move_lamports(offer, treasury, fee_amount)?;
+
+  msg!("Transfer royalties");
+  let mut royalty: u64 = 0;
+  for (creator, amount) in royalties(&None, mint, metadata_account, &mut creator_accounts.iter(), data.price)? {
+    move_lamports(offer, creator, amount)?;
+    royalty = add(royalty, amount)?;
+  }
+
+  msg!("Payment for token");
+  move_lamports(offer, owner, sub(sub(data.price, fee_amount)?, royalty)?)?;
+
+  emit(Event::SaleSettled {
+    buyer: data.bidder,
+    price: data.price,
+    seller: *owner.key,
+    storage: *offer.key
+  })?;
+
+  data.quantity = sub(data.quantity, 1)?;
+  if data.quantity == 0 {
+    msg!("Close collection offer");
+    close_lamports(offer, bidder)?;
+  }
+  else {
+    let _ = data.serialize(&mut &mut offer.data.borrow_mut()[..]);
+  }
+
+  Ok(())
+}
@@ -10,9 +10,11 @@ use solana_program::{
 use crate::{
   types::{
     share::{Share, ShareStorage, ShareData},
-    metadata::{CreateMetadataArgs, Data, Metadata}
+    metadata::{CreateMetadataArgs, Data, Metadata},
+    event::Event
   },
   token::mint_token::process_mint_token,
+  utils::event::emit,
   error::NftError,
   SHARER, SHARE_SEED
 };
@@ -145,5 +147,11 @@ pub fn process_create_share<'a>(
   storage.amount += 1;
   let _ = storage.serialize(&mut &mut share_storage.data.borrow_mut()[..]);
 
+  emit(Event::ShareMinted {
+    mint: *mint.key,
+    owner: *payer.key,
+    total: storage.amount as u64
+  })?;
+
   Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,13 @@
+use borsh::BorshSerialize;
+use solana_program::{
+  entrypoint::ProgramResult,
+  log::sol_log_data
+};
+use crate::types::event::Event;
+
+// событие в логах как "Program data: <base64>", индексатор декодирует его borsh
+pub fn emit(event: Event) -> ProgramResult {
+  let data = event.try_to_vec()?;
+  sol_log_data(&[&data]);
+  Ok(())
+}
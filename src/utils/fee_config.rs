@@ -0,0 +1,81 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  pubkey::Pubkey, msg,
+  entrypoint::ProgramResult,
+  account_info::AccountInfo,
+  program::invoke_signed,
+  program_error::ProgramError,
+  sysvar::{rent::Rent, Sysvar},
+  system_instruction
+};
+use crate::{
+  types::fee::FeeConfig,
+  error::NftError,
+  SHARER, FEE_SEED
+};
+
+// создание или изменение настроек комиссии маркетплейса
+pub fn process_set_fee_config<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  data: FeeConfig
+) -> ProgramResult {
+  if !payer.is_signer { return Err(NftError::AdminRequired.into()); }
+  if data.fee_bps > 10000 || data.cancel_penalty_bps > 10000 { return Err(NftError::WrongSellSettings.into()); }
+
+  let (calc_config, config_seed) = Pubkey::find_program_address(
+    &[FEE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_config != *fee_config.key { return Err(NftError::WrongSettingsPDA.into()); }
+  let config_signer_seeds = &[FEE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref(), &[config_seed]];
+
+  if fee_config.data_is_empty() {
+    // первый раз настройки создает администратор программы
+    if &payer.key.to_string() != SHARER { return Err(NftError::AdminRequired.into()); }
+
+    msg!("Create fee config");
+    let rent = &Rent::from_account_info(rent_program)?;
+    let space = data.try_to_vec()?.len();
+    let lamports = rent.minimum_balance(space);
+    invoke_signed(
+      &system_instruction::create_account(
+        payer.key,
+        fee_config.key,
+        lamports,
+        space as u64,
+        &program_id
+      ),
+      &[payer.clone(), fee_config.clone(), system_program.clone()],
+      &[config_signer_seeds],
+    )?;
+  }
+  else {
+    let config = FeeConfig::try_from_slice(&fee_config.data.borrow())?;
+    if config.admin != *payer.key { return Err(NftError::AdminRequired.into()); }
+  }
+
+  msg!("Save fee config");
+  let _ = data.serialize(&mut &mut fee_config.data.borrow_mut()[..]);
+
+  Ok(())
+}
+
+// настройки комиссии для путей продажи, treasury должен совпадать с настройками
+pub fn load_fee_config(
+  program_id: &Pubkey,
+  fee_config: &AccountInfo,
+  treasury: &AccountInfo
+) -> Result<FeeConfig, ProgramError> {
+  let (calc_config, _) = Pubkey::find_program_address(
+    &[FEE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_config != *fee_config.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = FeeConfig::try_from_slice(&fee_config.data.borrow())?;
+  if config.treasury != *treasury.key { return Err(ProgramError::InvalidArgument); }
+
+  Ok(config)
+}
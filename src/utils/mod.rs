@@ -1,6 +1,26 @@
 pub mod lottery_create;
 pub mod sell_create_storage;
+pub mod sell_create;
+pub mod sell_init;
+pub mod sell_storage;
 pub mod sell_withdrawal;
+pub mod sell_settle;
+pub mod sell_buy_now;
+pub mod sell_buy;
+pub mod sealed_bid;
+pub mod bid_history;
+pub mod sell_cancel;
+pub mod sell_update;
+pub mod sell_delegate;
+pub mod offer;
+pub mod collection_offer;
+pub mod bundle;
+pub mod amount;
+pub mod payment;
+pub mod royalty;
+pub mod fee_config;
+pub mod registry;
+pub mod event;
 pub mod lottery_check;
 pub mod del_pda;
 pub mod save_token;
@@ -0,0 +1,243 @@
+use solana_program::{
+  msg,
+  account_info::{next_account_info, AccountInfo},
+  entrypoint::ProgramResult,
+  program::{invoke, invoke_signed},
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{rent::Rent, Sysvar}
+};
+use crate::{
+  ESCROW,
+  error::NftError,
+  types::sell::Sell,
+  token::token_program::{check_token_program, unpack_account, unpack_mint, has_transfer_fee, account_len, transfer_checked},
+  utils::amount::move_lamports
+};
+use std::slice::Iter;
+
+// счета оплаты листинга: для SOL это кошельки и storage, для SPL токен аккаунты и escrow
+pub struct Payment<'a, 'b> {
+  pub mint: Option<&'b AccountInfo<'a>>,
+  pub escrow: &'b AccountInfo<'a>,
+  pub escrow_seed: u8,
+  pub buyer: &'b AccountInfo<'a>,
+  pub seller: &'b AccountInfo<'a>,
+  pub applicant: &'b AccountInfo<'a>,
+  pub treasury: &'b AccountInfo<'a>
+}
+
+// для SPL листинга следующие аккаунты: mint оплаты, escrow, токен аккаунты покупателя, продавца, претендента и treasury
+pub fn load_payment<'a, 'b>(
+  program_id: &Pubkey,
+  settings: &Sell,
+  storage: &'b AccountInfo<'a>,
+  buyer: &'b AccountInfo<'a>,
+  seller: &'b AccountInfo<'a>,
+  applicant: &'b AccountInfo<'a>,
+  treasury: &'b AccountInfo<'a>,
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>
+) -> Result<Payment<'a, 'b>, ProgramError> {
+  let payment_mint = match settings.payment_mint {
+    None => return Ok(Payment {
+      mint: None,
+      escrow: storage,
+      escrow_seed: 0,
+      buyer,
+      seller,
+      applicant,
+      treasury
+    }),
+    Some(payment_mint) => payment_mint
+  };
+
+  let mint = next_account_info(account_info_iter)?;
+  let escrow = next_account_info(account_info_iter)?;
+  let buyer_account = next_account_info(account_info_iter)?;
+  let seller_account = next_account_info(account_info_iter)?;
+  let applicant_account = next_account_info(account_info_iter)?;
+  let treasury_account = next_account_info(account_info_iter)?;
+
+  if *mint.key != payment_mint { return Err(ProgramError::InvalidArgument); }
+  // с комиссией за перевод escrow получал бы меньше ставки
+  if has_transfer_fee(mint)? { return Err(NftError::TransferFeeNotSupported.into()); }
+
+  let (calc_escrow, escrow_seed) = Pubkey::find_program_address(
+    &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref()], &program_id
+  );
+  if calc_escrow != *escrow.key { return Err(ProgramError::InvalidArgument); }
+
+  // аккаунт покупателя проверит token program при переводе с его подписью
+  check_payment_account(seller_account, &payment_mint, &settings.seller)?;
+  check_payment_account(applicant_account, &payment_mint, &settings.applicant)?;
+  check_payment_account(treasury_account, &payment_mint, treasury.key)?;
+
+  Ok(Payment {
+    mint: Some(mint),
+    escrow,
+    escrow_seed,
+    buyer: buyer_account,
+    seller: seller_account,
+    applicant: applicant_account,
+    treasury: treasury_account
+  })
+}
+
+// mint оплаты SPL листинга передается при создании, с комиссией за перевод листинг никто не смог бы оплатить
+pub fn check_payment_mint<'a, 'b>(
+  settings: &Sell,
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>
+) -> Result<Option<&'b AccountInfo<'a>>, ProgramError> {
+  let payment_mint = match settings.payment_mint {
+    None => return Ok(None),
+    Some(payment_mint) => payment_mint
+  };
+
+  let mint = next_account_info(account_info_iter)?;
+  if *mint.key != payment_mint { return Err(ProgramError::InvalidArgument); }
+  unpack_mint(mint)?;
+  if has_transfer_fee(mint)? { return Err(NftError::TransferFeeNotSupported.into()); }
+  Ok(Some(mint))
+}
+
+pub fn check_payment_account(
+  account: &AccountInfo,
+  payment_mint: &Pubkey,
+  owner: &Pubkey
+) -> ProgramResult {
+  let token_account = unpack_account(account)?;
+  if token_account.mint != *payment_mint { return Err(ProgramError::InvalidArgument); }
+  if token_account.owner != *owner { return Err(ProgramError::InvalidArgument); }
+  Ok(())
+}
+
+// перевод от подписанта: SOL через system program, SPL через token program
+pub fn pay<'a>(
+  payment_mint: Option<&AccountInfo<'a>>,
+  authority: &AccountInfo<'a>,
+  from: &AccountInfo<'a>,
+  to: &AccountInfo<'a>,
+  amount: u64,
+  token_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>
+) -> ProgramResult {
+  if amount == 0 { return Ok(()); }
+
+  match payment_mint {
+    None => invoke(
+      &system_instruction::transfer(from.key, to.key, amount),
+      &[from.clone(), to.clone(), system_program.clone()]
+    ),
+    Some(mint) => invoke(
+      &transfer_checked(token_program, from, mint, to, authority, amount)?,
+      &[from.clone(), mint.clone(), to.clone(), authority.clone(), token_program.clone()]
+    )
+  }
+}
+
+// выплата из storage (SOL) или escrow (SPL)
+pub fn pay_out<'a>(
+  payment_mint: Option<&AccountInfo<'a>>,
+  escrow: &AccountInfo<'a>,
+  to: &AccountInfo<'a>,
+  amount: u64,
+  token_program: &AccountInfo<'a>,
+  escrow_signer_seeds: &[&[u8]]
+) -> ProgramResult {
+  if amount == 0 { return Ok(()); }
+
+  match payment_mint {
+    None => move_lamports(escrow, to, amount),
+    Some(mint) => invoke_signed(
+      &transfer_checked(token_program, escrow, mint, to, escrow, amount)?,
+      &[escrow.clone(), mint.clone(), to.clone(), escrow.clone(), token_program.clone()],
+      &[escrow_signer_seeds]
+    )
+  }
+}
+
+// escrow SPL аукциона создается при листинге за счет продавца, следующий аккаунт после mint оплаты
+pub fn create_escrow<'a, 'b>(
+  program_id: &Pubkey,
+  settings: &Sell,
+  seller: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  payment_mint: Option<&AccountInfo<'a>>,
+  token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>
+) -> ProgramResult {
+  let mint = match payment_mint {
+    Some(mint) => mint,
+    None => return Ok(())
+  };
+  // ставки в escrow есть только у английского аукциона
+  if settings.auction != 1 { return Ok(()); }
+  check_token_program(token_program)?;
+  if mint.owner != token_program.key { return Err(NftError::WrongTokenProgram.into()); }
+
+  let escrow = next_account_info(account_info_iter)?;
+  let (calc_escrow, escrow_seed) = Pubkey::find_program_address(
+    &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref()], &program_id
+  );
+  if calc_escrow != *escrow.key { return Err(ProgramError::InvalidArgument); }
+  let escrow_signer_seeds = &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[escrow_seed]];
+
+  msg!("Create escrow");
+  let rent = &Rent::from_account_info(rent_program)?;
+  let escrow_len = account_len(mint)?;
+  invoke_signed(
+    &system_instruction::create_account(
+      seller.key,
+      escrow.key,
+      rent.minimum_balance(escrow_len),
+      escrow_len as u64,
+      token_program.key
+    ),
+    &[seller.clone(), escrow.clone(), system_program.clone()],
+    &[escrow_signer_seeds],
+  )?;
+
+  invoke(
+    &spl_token_2022::instruction::initialize_account(
+      token_program.key,
+      escrow.key,
+      mint.key,
+      escrow.key
+    )?,
+    &[escrow.clone(), mint.clone(), escrow.clone(), rent_program.clone(), token_program.clone()]
+  )
+}
+
+// остаток escrow уходит в treasury, рента escrow возвращается оплатившему ее продавцу
+pub fn close_escrow<'a>(
+  payment: &Payment<'a, '_>,
+  seller: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  escrow_signer_seeds: &[&[u8]]
+) -> ProgramResult {
+  let mint = match payment.mint {
+    Some(mint) => mint,
+    None => return Ok(())
+  };
+  if payment.escrow.data_is_empty() { return Ok(()); }
+  check_token_program(token_program)?;
+
+  let rest = unpack_account(payment.escrow)?.amount;
+  pay_out(Some(mint), payment.escrow, payment.treasury, rest, token_program, escrow_signer_seeds)?;
+
+  msg!("Close escrow");
+  invoke_signed(
+    &spl_token_2022::instruction::close_account(
+      token_program.key,
+      payment.escrow.key,
+      seller.key,
+      payment.escrow.key,
+      &[payment.escrow.key]
+    )?,
+    &[payment.escrow.clone(), seller.clone(), payment.escrow.clone(), token_program.clone()],
+    &[escrow_signer_seeds]
+  )
+}
@@ -0,0 +1,87 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program::invoke_signed,
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{rent::Rent, Sysvar}
+};
+use crate::{
+  REGISTRY,
+  error::NftError,
+  types::registry::ListingRegistry,
+  utils::amount::close_lamports
+};
+
+fn check_registry_pda(
+  program_id: &Pubkey,
+  mint: &Pubkey,
+  registry: &AccountInfo
+) -> Result<u8, ProgramError> {
+  let (calc_registry, registry_seed) = Pubkey::find_program_address(
+    &[REGISTRY.as_bytes(), program_id.as_ref(), mint.as_ref()], &program_id
+  );
+  if calc_registry != *registry.key { return Err(ProgramError::InvalidArgument); }
+  Ok(registry_seed)
+}
+
+// один активный листинг на mint, повторный листинг отклоняется
+pub fn open_registry<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  mint: &Pubkey,
+  storage: &Pubkey,
+  registry: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  auction: u8,
+  kind: u8
+) -> ProgramResult {
+  let registry_seed = check_registry_pda(program_id, mint, registry)?;
+  if !registry.data_is_empty() { return Err(NftError::AlreadyListed.into()); }
+  let registry_signer_seeds = &[REGISTRY.as_bytes(), program_id.as_ref(), mint.as_ref(), &[registry_seed]];
+
+  let data = ListingRegistry {
+    auction,
+    kind,
+    mint: *mint,
+    storage: *storage
+  };
+
+  let rent = &Rent::from_account_info(rent_program)?;
+  let space = data.try_to_vec()?.len();
+  invoke_signed(
+    &system_instruction::create_account(
+      payer.key,
+      registry.key,
+      rent.minimum_balance(space),
+      space as u64,
+      &program_id
+    ),
+    &[payer.clone(), registry.clone(), system_program.clone()],
+    &[registry_signer_seeds],
+  )?;
+  let _ = data.serialize(&mut &mut registry.data.borrow_mut()[..]);
+
+  Ok(())
+}
+
+// листинг закрыт: запись удаляется, аренда возвращается продавцу
+// у листингов, созданных до реестра, записи нет
+pub fn close_registry<'a>(
+  program_id: &Pubkey,
+  mint: &Pubkey,
+  storage: &Pubkey,
+  registry: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>
+) -> ProgramResult {
+  check_registry_pda(program_id, mint, registry)?;
+  if registry.data_is_empty() { return Ok(()); }
+
+  let data = ListingRegistry::try_from_slice(&registry.data.borrow())?;
+  if data.storage != *storage { return Err(ProgramError::InvalidArgument); }
+
+  close_lamports(registry, seller)
+}
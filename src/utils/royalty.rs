@@ -0,0 +1,87 @@
+use borsh::BorshDeserialize;
+use solana_program::{
+  account_info::{next_account_info, AccountInfo},
+  program_error::ProgramError,
+  pubkey::Pubkey
+};
+use crate::{
+  METADATA_PROGRAM_ID,
+  types::metadata::{Data, DataTail, Collection},
+  utils::{
+    payment::check_payment_account,
+    amount::{bps_of, mul_div}
+  }
+};
+use arrayref::array_ref;
+use std::str::FromStr;
+use std::slice::Iter;
+
+// данные токена из metaplex metadata: key, update_authority, mint, data
+pub fn read_metadata(
+  mint: &AccountInfo,
+  metadata_account: &AccountInfo
+) -> Result<Data, ProgramError> {
+  Ok(read_metadata_full(mint, metadata_account)?.0)
+}
+
+// коллекция токена из metadata, у старых metadata без хвоста коллекции нет
+pub fn read_collection(
+  mint: &AccountInfo,
+  metadata_account: &AccountInfo
+) -> Result<Option<Collection>, ProgramError> {
+  Ok(read_metadata_full(mint, metadata_account)?.1.and_then(|tail| tail.collection))
+}
+
+fn read_metadata_full(
+  mint: &AccountInfo,
+  metadata_account: &AccountInfo
+) -> Result<(Data, Option<DataTail>), ProgramError> {
+  let metadata_program_id = Pubkey::from_str(METADATA_PROGRAM_ID).map_err(|_| ProgramError::InvalidArgument)?;
+  if *metadata_account.owner != metadata_program_id { return Err(ProgramError::InvalidArgument); }
+
+  let (calc_metadata, _) = Pubkey::find_program_address(
+    &["metadata".as_bytes(), metadata_program_id.as_ref(), mint.key.as_ref()], &metadata_program_id
+  );
+  if calc_metadata != *metadata_account.key { return Err(ProgramError::InvalidArgument); }
+
+  let data = metadata_account.try_borrow_data()?;
+  if data.len() < 65 { return Err(ProgramError::InvalidAccountData); }
+  if Pubkey::new_from_array(*array_ref![data, 33, 32]) != *mint.key { return Err(ProgramError::InvalidAccountData); }
+
+  let buf = &mut &data[65..];
+  let meta = Data::deserialize(buf)?;
+  let tail = DataTail::deserialize(buf).ok();
+  Ok((meta, tail))
+}
+
+// выплаты верифицированным создателям по их share, аккаунты создателей передаются в том же порядке
+// для SPL листинга это токен аккаунты создателей в mint оплаты
+pub fn royalties<'a, 'b>(
+  payment_mint: &Option<Pubkey>,
+  mint: &AccountInfo,
+  metadata_account: &AccountInfo,
+  account_info_iter: &mut Iter<'b, AccountInfo<'a>>,
+  price: u64
+) -> Result<Vec<(&'b AccountInfo<'a>, u64)>, ProgramError> {
+  let meta = read_metadata(mint, metadata_account)?;
+  let mut payouts = Vec::new();
+
+  let creators = match meta.creators {
+    Some(creators) => creators,
+    None => return Ok(payouts)
+  };
+  if meta.seller_fee_basis_points == 0 { return Ok(payouts); }
+
+  for creator in creators.iter().filter(|c| c.verified) {
+    let creator_account = next_account_info(account_info_iter)?;
+    match payment_mint {
+      None => if *creator_account.key != creator.address { return Err(ProgramError::InvalidArgument); },
+      Some(payment_mint) => check_payment_account(creator_account, payment_mint, &creator.address)?
+    }
+
+    let amount = mul_div(bps_of(price, meta.seller_fee_basis_points)?, creator.share as u64, 100)?;
+    payouts.push((creator_account, amount));
+  }
+
+  Ok(payouts)
+}
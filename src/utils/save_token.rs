@@ -9,7 +9,10 @@ use solana_program::{
 };
 use crate::{
   error::NftError, LOTTERY_NFT, LOTTERY_SEED,
-  token::mint_token::process_mint_token,
+  token::{
+    mint_token::process_mint_token,
+    token_program::unpack_mint
+  },
   types::{
     nft_lottery::{NftLottery, BoxData, SaveToken},
     lottery::Lottery,
@@ -17,7 +20,6 @@ use crate::{
   },
   SHARE_SEED
 };
-use solana_program::program_pack::Pack;
 
 pub fn process_save_token<'a>(
   program_id: &Pubkey,
@@ -46,7 +48,7 @@ pub fn process_save_token<'a>(
   if burned_token_storage.data_is_empty() { return Err(NftError::WrongOwnerNFR.into()); }
 
   // проверка что действительно токена нет
-  let mint_data = spl_token::state::Mint::unpack(&burned_token.data.borrow())?;
+  let mint_data = unpack_mint(burned_token)?;
   if mint_data.supply > 0 { return Err(NftError::WrongSettingsPDA.into()); }
   drop(mint_data);
 
@@ -0,0 +1,323 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  hash::hashv,
+  program::invoke_signed,
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{clock::Clock, rent::Rent, Sysvar}
+};
+use crate::{
+  STORAGE, SHARE_SEED, SEALED_BID,
+  error::NftError,
+  types::{
+    sell::Sell,
+    sealed_bid::SealedBid,
+    event::Event
+  },
+  token::{
+    transfer_token_seed::process_transfer_token_seed,
+    token_program::unpack_account
+  },
+  utils::{
+    sell_storage::{load_sell, save_sell},
+    sell_withdrawal::check_sell_pda,
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::close_registry,
+    event::emit,
+    amount::{bps_of, add, sub, move_lamports, close_lamports}
+  }
+};
+
+fn check_bid_pda(
+  program_id: &Pubkey,
+  storage: &Pubkey,
+  bidder: &Pubkey,
+  bid: &AccountInfo
+) -> Result<u8, ProgramError> {
+  let (calc_bid, bid_seed) = Pubkey::find_program_address(
+    &[SEALED_BID.as_bytes(), program_id.as_ref(), storage.as_ref(), bidder.as_ref()], &program_id
+  );
+  if calc_bid != *bid.key { return Err(ProgramError::InvalidArgument); }
+  Ok(bid_seed)
+}
+
+// ставки раскрываются с end_time до end_time + reveal_time
+fn reveal_end(settings: &Sell) -> Result<u64, ProgramError> {
+  add(settings.end_time, settings.reveal_time)
+}
+
+// хеш закрытой ставки привязан к листингу и участнику, чужой хеш нельзя повторить
+fn bid_commitment(
+  storage: &Pubkey,
+  bidder: &Pubkey,
+  listing: u64,
+  amount: u64,
+  salt: &[u8; 32]
+) -> [u8; 32] {
+  hashv(&[storage.as_ref(), bidder.as_ref(), &listing.to_le_bytes(), &amount.to_le_bytes(), salt]).to_bytes()
+}
+
+// закрытая ставка: хеш (storage, bidder, start_time, amount, salt) и депозит в SOL на аккаунте ставки
+pub fn process_sealed_commit<'a>(
+  program_id: &Pubkey,
+  bidder: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  bid: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  commitment: [u8; 32],
+  deposit: u64
+) -> ProgramResult {
+  if !bidder.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+  if deposit == 0 { return Err(NftError::WrongSellSettings.into()); }
+  if !bid.data_is_empty() { return Err(ProgramError::AccountAlreadyInitialized); }
+
+  let mut settings = load_sell(storage)?;
+  if settings.auction != 3 { return Err(ProgramError::InvalidArgument); }
+  if settings.seller == *bidder.key { return Err(ProgramError::InvalidArgument); }
+  check_sell_pda(program_id, &settings, mint, vault, storage)?;
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  if now >= settings.end_time { return Err(NftError::AuctionEnded.into()); }
+
+  let bid_seed = check_bid_pda(program_id, storage.key, bidder.key, bid)?;
+  let bid_signer_seeds = &[SEALED_BID.as_bytes(), program_id.as_ref(), storage.key.as_ref(), bidder.key.as_ref(), &[bid_seed]];
+
+  let data = SealedBid {
+    amount: 0,
+    bidder: *bidder.key,
+    commitment,
+    deposit,
+    listing: settings.start_time,
+    storage: *storage.key
+  };
+
+  msg!("Create sealed bid");
+  let rent = &Rent::from_account_info(rent_program)?;
+  let space = data.try_to_vec()?.len();
+  let lamports = add(rent.minimum_balance(space), deposit)?;
+  invoke_signed(
+    &system_instruction::create_account(
+      bidder.key,
+      bid.key,
+      lamports,
+      space as u64,
+      &program_id
+    ),
+    &[bidder.clone(), bid.clone(), system_program.clone()],
+    &[bid_signer_seeds],
+  )?;
+  let _ = data.serialize(&mut &mut bid.data.borrow_mut()[..]);
+
+  settings.bets = add(settings.bets, 1)?;
+  save_sell(&settings, storage)?;
+
+  Ok(())
+}
+
+// раскрытие ставки, лидер и вторая цена обновляются сразу
+pub fn process_sealed_reveal<'a>(
+  program_id: &Pubkey,
+  bidder: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  bid: &AccountInfo<'a>,
+  amount: u64,
+  salt: [u8; 32]
+) -> ProgramResult {
+  if !bidder.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let mut settings = load_sell(storage)?;
+  if settings.auction != 3 { return Err(ProgramError::InvalidArgument); }
+
+  let mut data = SealedBid::try_from_slice(&bid.data.borrow())?;
+  if data.bidder != *bidder.key { return Err(ProgramError::InvalidArgument); }
+  if data.storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+  if data.listing != settings.start_time { return Err(ProgramError::InvalidArgument); }
+  check_bid_pda(program_id, storage.key, bidder.key, bid)?;
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  if now < settings.end_time || now >= reveal_end(&settings)? { return Err(NftError::NotRevealPhase.into()); }
+
+  if data.amount != 0 { return Err(ProgramError::InvalidArgument); }
+  if amount == 0 || amount > data.deposit { return Err(NftError::WrongCommitment.into()); }
+  if bid_commitment(storage.key, bidder.key, data.listing, amount, &salt) != data.commitment {
+    return Err(NftError::WrongCommitment.into());
+  }
+
+  data.amount = amount;
+  let _ = data.serialize(&mut &mut bid.data.borrow_mut()[..]);
+
+  // ставка ниже резерва раскрыта, но не участвует
+  if amount >= settings.reserve_price {
+    if amount > settings.price {
+      settings.second_price = settings.second_price.max(settings.price);
+      settings.price = amount;
+      settings.applicant = *bidder.key;
+    }
+    else if amount > settings.second_price {
+      settings.second_price = amount;
+    }
+    save_sell(&settings, storage)?;
+  }
+
+  emit(Event::BidPlaced {
+    amount,
+    bidder: *bidder.key,
+    storage: *storage.key
+  })?;
+
+  msg!("Bid revealed");
+  Ok(())
+}
+
+// возврат депозита проигравшей или нераскрытой ставки после фазы раскрытия
+pub fn process_sealed_claim<'a>(
+  program_id: &Pubkey,
+  bidder: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  bid: &AccountInfo<'a>
+) -> ProgramResult {
+  if !bidder.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let data = SealedBid::try_from_slice(&bid.data.borrow())?;
+  if data.bidder != *bidder.key { return Err(ProgramError::InvalidArgument); }
+  if data.storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+  check_bid_pda(program_id, storage.key, bidder.key, bid)?;
+
+  // после закрытия аукциона storage пустой, все оставшиеся ставки проиграли
+  if !storage.data_is_empty() {
+    let settings = load_sell(storage)?;
+    if settings.auction == 3 && settings.start_time == data.listing {
+      let now = Clock::get()?.unix_timestamp as u64;
+      if now < reveal_end(&settings)? { return Err(NftError::AuctionNotEnded.into()); }
+      // депозит победителя забирается при закрытии аукциона
+      if settings.applicant == *bidder.key { return Err(ProgramError::InvalidArgument); }
+    }
+  }
+
+  emit(Event::BidRefunded {
+    amount: data.deposit,
+    bidder: *bidder.key,
+    storage: *bid.key
+  })?;
+
+  msg!("Close sealed bid");
+  close_lamports(bid, bidder)?;
+
+  Ok(())
+}
+
+// закрытие аукциона после фазы раскрытия, вызвать может кто угодно
+// токен победителю, оплата из его депозита по правилу цены, остаток депозита победителю
+pub fn process_sealed_settle<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  bid: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  creator_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let settings = load_sell(storage)?;
+  if settings.auction != 3 { return Err(ProgramError::InvalidArgument); }
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if settings.applicant != *applicant.key { return Err(ProgramError::InvalidArgument); }
+
+  let vault_seed = check_sell_pda(program_id, &settings, mint, vault, storage)?;
+  let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  if now < reveal_end(&settings)? { return Err(NftError::AuctionNotEnded.into()); }
+
+  // победитель есть, только если раскрыта ставка не ниже резерва
+  let has_winner = settings.applicant != settings.seller;
+  let winner = if has_winner { applicant } else { seller };
+
+  msg!("Transfer token to auction winner and close auction");
+  process_transfer_token_seed(
+    payer,
+    winner,
+    mint,
+    vault,
+    token_transfer_account,
+    profit_id,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program,
+    vault_signer_seeds,
+    1
+  )?;
+
+  let spl_token_account = unpack_account(token_transfer_account)?;
+  if spl_token_account.owner != *winner.key { return Err(ProgramError::InvalidArgument); }
+
+  if has_winner {
+    let data = SealedBid::try_from_slice(&bid.data.borrow())?;
+    if data.bidder != settings.applicant { return Err(ProgramError::InvalidArgument); }
+    if data.storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+    check_bid_pda(program_id, storage.key, applicant.key, bid)?;
+
+    let price = if settings.price_rule == 1 { settings.second_price } else { settings.price };
+
+    msg!("Transfer fee");
+    let fee_amount = bps_of(price, config.fee_bps)?;
+    move_lamports(bid, treasury, fee_amount)?;
+
+    msg!("Transfer royalties");
+    let mut royalty: u64 = 0;
+    for (creator, amount) in royalties(&None, mint, metadata_account, &mut creator_accounts.iter(), price)? {
+      move_lamports(bid, creator, amount)?;
+      royalty = add(royalty, amount)?;
+    }
+
+    msg!("Payment for token");
+    move_lamports(bid, seller, sub(sub(price, fee_amount)?, royalty)?)?;
+
+    msg!("Close sealed bid");
+    close_lamports(bid, applicant)?;
+
+    emit(Event::SaleSettled {
+      buyer: settings.applicant,
+      price,
+      seller: settings.seller,
+      storage: *storage.key
+    })?;
+  }
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, profit_id)?;
+
+  msg!("Sealed-bid auction done");
+  Ok(())
+}
@@ -0,0 +1,160 @@
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program::invoke,
+  pubkey::Pubkey,
+  program_error::ProgramError,
+  sysvar::{clock::Clock, Sysvar}
+};
+use crate::{
+  STORAGE, SHARE_SEED,
+  error::NftError,
+  types::event::Event,
+  token::{
+    transfer_token_seed::process_transfer_token_seed,
+    token_program::{check_mint, gross_amount, transfer_checked, unpack_account}
+  },
+  utils::{
+    sell_storage::{load_sell, save_sell},
+    sell_withdrawal::{check_sell_pda, check_buyer, pay_sale},
+    payment::load_payment,
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::close_registry,
+    event::emit,
+    amount::{mul, sub, close_lamports}
+  }
+};
+
+// пополнение vault до количества листинга с аккаунта продавца
+// комиссию Token-2022 за перевод платит продавец
+pub fn fill_vault<'a>(
+  seller: &AccountInfo<'a>,
+  token_account: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  quantity: u64
+) -> ProgramResult {
+  check_mint(mint, token_program)?;
+
+  let vault_amount = unpack_account(vault)?.amount;
+  if vault_amount >= quantity { return Ok(()); }
+
+  msg!("Transfer tokens to vault");
+  invoke(
+    &transfer_checked(
+      token_program,
+      token_account,
+      mint,
+      vault,
+      seller,
+      gross_amount(mint, sub(quantity, vault_amount)?)?
+    )?,
+    &[token_program.clone(), token_account.clone(), mint.clone(), vault.clone(), seller.clone()]
+  )?;
+
+  if unpack_account(vault)?.amount < quantity { return Err(NftError::NotEnoughQuantity.into()); }
+  Ok(())
+}
+
+// покупка части листинга по цене за единицу, storage и vault закрываются с последней единицей
+pub fn process_sell_buy<'a>(
+  program_id: &Pubkey,
+  mint: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  buyer: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>],
+  amount: u64
+) -> ProgramResult {
+  if !buyer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let mut settings = load_sell(storage)?;
+  if settings.auction != 0 { return Err(ProgramError::InvalidArgument); }
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if settings.seller == *buyer.key { return Err(ProgramError::InvalidArgument); }
+  if amount == 0 || amount > settings.quantity { return Err(NftError::NotEnoughQuantity.into()); }
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  check_buyer(&settings, buyer.key, now)?;
+
+  let vault_seed = check_sell_pda(program_id, &settings, mint, vault, storage)?;
+  let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
+
+  // после счетов оплаты идут аккаунты создателей для роялти
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &settings, storage, buyer, seller, seller, treasury, account_info_iter)?;
+
+  let price = mul(settings.price, amount)?;
+  let payouts = royalties(&settings.payment_mint, mint, metadata_account, account_info_iter, price)?;
+  pay_sale(
+    &config,
+    price,
+    buyer,
+    &payment,
+    &payouts,
+    token_program,
+    system_program
+  )?;
+
+  msg!("Transfer tokens");
+  process_transfer_token_seed(
+    buyer,
+    buyer,
+    mint,
+    vault,
+    token_transfer_account,
+    profit_id,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program,
+    vault_signer_seeds,
+    amount
+  )?;
+
+  let spl_token_account = unpack_account(token_transfer_account)?;
+  if spl_token_account.owner != *buyer.key { return Err(ProgramError::InvalidArgument); }
+
+  emit(Event::SaleSettled {
+    buyer: *buyer.key,
+    price,
+    seller: settings.seller,
+    storage: *storage.key
+  })?;
+
+  settings.quantity = sub(settings.quantity, amount)?;
+  if settings.quantity > 0 {
+    save_sell(&settings, storage)?;
+
+    msg!("Partial sale done");
+    return Ok(());
+  }
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  close_lamports(storage, profit_id)?;
+
+  msg!("Process sale done");
+  Ok(())
+}
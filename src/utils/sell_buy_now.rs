@@ -0,0 +1,136 @@
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  pubkey::Pubkey,
+  program_error::ProgramError,
+  sysvar::{clock::Clock, Sysvar}
+};
+use crate::{
+  STORAGE, SHARE_SEED, ESCROW,
+  error::NftError,
+  types::event::Event,
+  token::{
+    transfer_token_seed::process_transfer_token_seed,
+    token_program::unpack_account
+  },
+  utils::{
+    sell_storage::load_sell,
+    sell_withdrawal::{check_sell_pda, pay_sale},
+    payment::{load_payment, pay_out, close_escrow},
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::close_registry,
+    event::emit,
+    amount::close_lamports
+  }
+};
+
+// выкуп токена с аукциона по buy_now_price, текущему претенденту ставка возвращается полностью
+pub fn process_sell_buy_now<'a>(
+  program_id: &Pubkey,
+  mint: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  buyer: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !buyer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let settings = load_sell(storage)?;
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if settings.applicant != *applicant.key { return Err(ProgramError::InvalidArgument); }
+  if settings.seller == *buyer.key { return Err(ProgramError::InvalidArgument); }
+
+  // выкуп доступен, пока ставки не догнали buy_now_price
+  if settings.auction != 1 || settings.buy_now_price == 0 { return Err(NftError::BuyNowUnavailable.into()); }
+  if settings.price >= settings.buy_now_price { return Err(NftError::BuyNowUnavailable.into()); }
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  if now >= settings.end_time { return Err(NftError::AuctionEnded.into()); }
+
+  let vault_seed = check_sell_pda(program_id, &settings, mint, vault, storage)?;
+  let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
+
+  // после счетов оплаты идут аккаунты создателей для роялти
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &settings, storage, buyer, seller, applicant, treasury, account_info_iter)?;
+  let escrow_signer_seeds = &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[payment.escrow_seed]];
+
+  if settings.applicant != settings.seller {
+    msg!("Refund bet");
+    pay_out(payment.mint, payment.escrow, payment.applicant, settings.price, token_program, escrow_signer_seeds)?;
+
+    emit(Event::BidRefunded {
+      amount: settings.price,
+      bidder: settings.applicant,
+      storage: *storage.key
+    })?;
+  }
+
+  let payouts = royalties(&settings.payment_mint, mint, metadata_account, account_info_iter, settings.buy_now_price)?;
+  pay_sale(
+    &config,
+    settings.buy_now_price,
+    buyer,
+    &payment,
+    &payouts,
+    token_program,
+    system_program
+  )?;
+
+  msg!("Transfer token");
+  process_transfer_token_seed(
+    buyer,
+    buyer,
+    mint,
+    vault,
+    token_transfer_account,
+    profit_id,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program,
+    vault_signer_seeds,
+    1
+  )?;
+
+  let spl_token_account = unpack_account(token_transfer_account)?;
+  if spl_token_account.owner != *buyer.key { return Err(ProgramError::InvalidArgument); }
+
+  close_escrow(&payment, seller, token_program, escrow_signer_seeds)?;
+
+  emit(Event::SaleSettled {
+    buyer: *buyer.key,
+    price: settings.buy_now_price,
+    seller: settings.seller,
+    storage: *storage.key
+  })?;
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, profit_id)?;
+
+  msg!("Buy now done");
+  Ok(())
+}
@@ -0,0 +1,81 @@
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  pubkey::Pubkey,
+  program_error::ProgramError,
+  sysvar::{clock::Clock, Sysvar}
+};
+use crate::{
+  STORAGE,
+  error::NftError,
+  token::{
+    transfer_token_seed::process_transfer_token_seed,
+    token_program::unpack_account
+  },
+  utils::{
+    sell_storage::load_sell,
+    sell_withdrawal::{check_sell_pda, sell_expired},
+    registry::close_registry,
+    amount::close_lamports
+  }
+};
+
+// снятие листинга с продажи, аренда vault и storage возвращается продавцу
+// просроченный листинг может закрыть кто угодно
+pub fn process_sell_cancel<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let settings = load_sell(storage)?;
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  // аукционы закрываются через SettleAuction и SettleSealedBid
+  if settings.auction == 1 || settings.auction == 3 { return Err(ProgramError::InvalidArgument); }
+
+  if *payer.key != settings.seller {
+    let now = Clock::get()?.unix_timestamp as u64;
+    if !sell_expired(&settings, now) { return Err(NftError::ListingNotExpired.into()); }
+  }
+
+  let vault_seed = check_sell_pda(program_id, &settings, mint, vault, storage)?;
+  let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
+
+  msg!("Return token to seller");
+  process_transfer_token_seed(
+    payer,
+    seller,
+    mint,
+    vault,
+    token_transfer_account,
+    seller,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program,
+    vault_signer_seeds,
+    settings.quantity
+  )?;
+
+  let spl_token_account = unpack_account(token_transfer_account)?;
+  if spl_token_account.owner != settings.seller { return Err(ProgramError::InvalidArgument); }
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, seller)?;
+
+  msg!("Listing cancelled");
+  Ok(())
+}
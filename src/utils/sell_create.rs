@@ -0,0 +1,102 @@
+use solana_program::{
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  pubkey::Pubkey
+};
+use crate::{
+  types::{
+    sell::Sell,
+    event::Event
+  },
+  utils::{
+    sell_init::init_sell,
+    sell_create_storage::process_sell_create_storage,
+    sell_buy::fill_vault,
+    sell_delegate::revoke_stale_delegate,
+    payment::{check_payment_mint, create_escrow},
+    registry::open_registry,
+    event::emit
+  }
+};
+
+// листинг через vault: storage и перевод токена, vault пополняется до количества листинга, запись в реестре
+pub fn process_sell_create<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  token_account: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>],
+  mut sell: Sell,
+  duration: u64
+) -> ProgramResult {
+  init_sell(&mut sell, duration)?;
+
+  // для SPL листинга дальше идет mint оплаты, для аукциона еще escrow
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment_mint = check_payment_mint(&sell, account_info_iter)?;
+  create_escrow(
+    program_id,
+    &sell,
+    payer,
+    storage,
+    payment_mint,
+    token_program,
+    rent_program,
+    system_program,
+    account_info_iter
+  )?;
+
+  let auction = sell.auction;
+  let price = sell.price;
+  let quantity = sell.quantity;
+
+  process_sell_create_storage(
+    program_id,
+    payer,
+    token_account,
+    mint,
+    vault,
+    storage,
+    token_program,
+    rent_program,
+    system_program,
+    sell
+  )?;
+
+  revoke_stale_delegate(payer, token_account, vault, token_program)?;
+
+  fill_vault(
+    payer,
+    token_account,
+    mint,
+    vault,
+    token_program,
+    quantity
+  )?;
+
+  open_registry(
+    program_id,
+    payer,
+    mint.key,
+    storage.key,
+    registry,
+    rent_program,
+    system_program,
+    auction,
+    0
+  )?;
+
+  emit(Event::ListingCreated {
+    auction,
+    mint: *mint.key,
+    price,
+    seller: *payer.key,
+    storage: *storage.key
+  })
+}
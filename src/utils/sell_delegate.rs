@@ -0,0 +1,322 @@
+use borsh::BorshSerialize;
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program::{invoke, invoke_signed},
+  program_error::ProgramError,
+  pubkey::Pubkey,
+  system_instruction,
+  sysvar::{clock::Clock, rent::Rent, Sysvar}
+};
+use crate::{
+  STORAGE, SHARE_SEED,
+  error::NftError,
+  types::{
+    sell::Sell,
+    event::Event
+  },
+  token::{
+    transfer_token_delegate::process_transfer_token_delegate,
+    token_program::{check_mint, check_token_program, unpack_account}
+  },
+  utils::{
+    sell_init::init_sell,
+    sell_storage::{load_sell, save_sell},
+    sell_withdrawal::{sell_expired, check_buyer, pay_sale},
+    payment::{load_payment, check_payment_mint},
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::{open_registry, close_registry},
+    event::emit,
+    amount::close_lamports
+  }
+};
+use solana_program::program_option::COption;
+
+// листинг без vault: токен остается у продавца, PDA [STORAGE, mint] одобрен делегатом
+// storage [STORAGE, токен аккаунт продавца], в sell.vault лежит токен аккаунт продавца
+fn check_delegate_pda(
+  program_id: &Pubkey,
+  settings: &Sell,
+  mint: &AccountInfo,
+  seller_account: &AccountInfo,
+  delegate: &AccountInfo,
+  storage: &AccountInfo
+) -> Result<(u8, u8), ProgramError> {
+  if settings.vault != *seller_account.key { return Err(ProgramError::InvalidArgument); }
+
+  let (calc_delegate, delegate_seed) = Pubkey::find_program_address(
+    &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref()], &program_id
+  );
+  if calc_delegate != *delegate.key { return Err(ProgramError::InvalidArgument); }
+
+  let (calc_storage, storage_seed) = Pubkey::find_program_address(
+    &[STORAGE.as_bytes(), program_id.as_ref(), seller_account.key.as_ref()], &program_id
+  );
+  if calc_storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+
+  Ok((delegate_seed, storage_seed))
+}
+
+// одобрение остается на аккаунте продавца, если листинг снял не продавец
+// такое одобрение без storage ничего не дает и отзывается при следующем листинге с этого аккаунта
+// PDA делегата совпадает с vault того же mint
+pub fn revoke_stale_delegate<'a>(
+  seller: &AccountInfo<'a>,
+  seller_account: &AccountInfo<'a>,
+  delegate: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>
+) -> ProgramResult {
+  check_token_program(token_program)?;
+  let token_account = unpack_account(seller_account)?;
+  if token_account.owner != *seller.key || token_account.delegate != COption::Some(*delegate.key) { return Ok(()); }
+
+  msg!("Revoke delegate");
+  invoke(
+    &spl_token_2022::instruction::revoke(
+      token_program.key,
+      seller_account.key,
+      seller.key,
+      &[seller.key]
+    )?,
+    &[seller_account.clone(), seller.clone(), token_program.clone()]
+  )
+}
+
+// листинг действителен, пока токен у продавца и одобрение делегату не отозвано
+fn delegate_valid(
+  settings: &Sell,
+  mint: &AccountInfo,
+  seller_account: &AccountInfo,
+  delegate: &AccountInfo
+) -> Result<bool, ProgramError> {
+  if seller_account.data_is_empty() { return Ok(false); }
+
+  let token_account = unpack_account(seller_account)?;
+  Ok(
+    token_account.owner == settings.seller
+    && token_account.mint == *mint.key
+    && token_account.amount >= 1
+    && token_account.delegate == COption::Some(*delegate.key)
+    && token_account.delegated_amount >= 1
+  )
+}
+
+pub fn process_sell_delegate_create<'a>(
+  program_id: &Pubkey,
+  seller: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  seller_account: &AccountInfo<'a>,
+  delegate: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>],
+  mut sell: Sell,
+  duration: u64
+) -> ProgramResult {
+  if !seller.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+  if !storage.data_is_empty() { return Err(ProgramError::AccountAlreadyInitialized); }
+  init_sell(&mut sell, duration)?;
+  // без vault только продажа одного токена по фиксированной цене
+  if sell.auction != 0 || sell.quantity != 1 { return Err(NftError::WrongSellSettings.into()); }
+  check_mint(mint, token_program)?;
+  // для SPL листинга дальше идет mint оплаты
+  check_payment_mint(&sell, &mut remaining_accounts.iter())?;
+
+  let token_account = unpack_account(seller_account)?;
+  if token_account.owner != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if token_account.mint != *mint.key { return Err(ProgramError::InvalidArgument); }
+  if token_account.amount < 1 { return Err(ProgramError::InsufficientFunds); }
+
+  sell.seller = *seller.key;
+  sell.applicant = *seller.key;
+  sell.bets = 0;
+  sell.vault = *seller_account.key;
+
+  let (_, storage_seed) = check_delegate_pda(program_id, &sell, mint, seller_account, delegate, storage)?;
+
+  // новое одобрение заменяет оставшееся от прошлого листинга
+  msg!("Approve delegate");
+  invoke(
+    &spl_token_2022::instruction::approve(
+      token_program.key,
+      seller_account.key,
+      delegate.key,
+      seller.key,
+      &[seller.key],
+      1
+    )?,
+    &[seller_account.clone(), delegate.clone(), seller.clone(), token_program.clone()]
+  )?;
+
+  let storage_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), seller_account.key.as_ref(), &[storage_seed]];
+
+  msg!("Create storage");
+  let rent = &Rent::from_account_info(rent_program)?;
+  let space = sell.try_to_vec()?.len();
+  invoke_signed(
+    &system_instruction::create_account(
+      seller.key,
+      storage.key,
+      rent.minimum_balance(space),
+      space as u64,
+      &program_id
+    ),
+    &[seller.clone(), storage.clone(), system_program.clone()],
+    &[storage_signer_seeds],
+  )?;
+  save_sell(&sell, storage)?;
+
+  open_registry(
+    program_id,
+    seller,
+    mint.key,
+    storage.key,
+    registry,
+    rent_program,
+    system_program,
+    sell.auction,
+    2
+  )?;
+
+  emit(Event::ListingCreated {
+    auction: sell.auction,
+    mint: *mint.key,
+    price: sell.price,
+    seller: *seller.key,
+    storage: *storage.key
+  })
+}
+
+// покупка: оплата как у обычного листинга, токен переводится делегатом с аккаунта продавца
+pub fn process_sell_delegate_buy<'a>(
+  program_id: &Pubkey,
+  mint: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  buyer: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  seller_account: &AccountInfo<'a>,
+  delegate: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !buyer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let settings = load_sell(storage)?;
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if settings.seller == *buyer.key { return Err(ProgramError::InvalidArgument); }
+
+  let (delegate_seed, _) = check_delegate_pda(program_id, &settings, mint, seller_account, delegate, storage)?;
+  let delegate_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[delegate_seed]];
+
+  if !delegate_valid(&settings, mint, seller_account, delegate)? { return Err(NftError::ListingInvalid.into()); }
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  check_buyer(&settings, buyer.key, now)?;
+
+  // после счетов оплаты идут аккаунты создателей для роялти
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &settings, storage, buyer, seller, seller, treasury, account_info_iter)?;
+
+  let payouts = royalties(&settings.payment_mint, mint, metadata_account, account_info_iter, settings.price)?;
+  pay_sale(
+    &config,
+    settings.price,
+    buyer,
+    &payment,
+    &payouts,
+    token_program,
+    system_program
+  )?;
+
+  msg!("Transfer token");
+  process_transfer_token_delegate(
+    buyer,
+    buyer,
+    mint,
+    seller_account,
+    token_transfer_account,
+    delegate,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program,
+    delegate_signer_seeds
+  )?;
+
+  let spl_token_account = unpack_account(token_transfer_account)?;
+  if spl_token_account.owner != *buyer.key { return Err(ProgramError::InvalidArgument); }
+
+  emit(Event::SaleSettled {
+    buyer: *buyer.key,
+    price: settings.price,
+    seller: settings.seller,
+    storage: *storage.key
+  })?;
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  close_lamports(storage, profit_id)?;
+
+  msg!("Process delegate sale done");
+  Ok(())
+}
+
+// снятие листинга продавцом, просроченный или недействительный листинг может закрыть кто угодно
+pub fn process_sell_delegate_cancel<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  seller_account: &AccountInfo<'a>,
+  delegate: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let settings = load_sell(storage)?;
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  check_delegate_pda(program_id, &settings, mint, seller_account, delegate, storage)?;
+
+  if *payer.key == settings.seller {
+    // отзываем одобрение, если токен еще у продавца
+    revoke_stale_delegate(seller, seller_account, delegate, token_program)?;
+  }
+  else {
+    // отозвать одобрение без подписи продавца нельзя, оно отзывается при его следующем листинге
+    let now = Clock::get()?.unix_timestamp as u64;
+    if !sell_expired(&settings, now) && delegate_valid(&settings, mint, seller_account, delegate)? {
+      return Err(NftError::ListingNotExpired.into());
+    }
+  }
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, seller)?;
+
+  msg!("Listing cancelled");
+  Ok(())
+}
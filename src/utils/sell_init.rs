@@ -0,0 +1,112 @@
+use solana_program::{
+  entrypoint::ProgramResult,
+  sysvar::{clock::Clock, Sysvar}
+};
+use crate::{
+  SELL_VERSION,
+  error::NftError,
+  types::sell::Sell,
+  utils::amount::add
+};
+
+// выставляет сроки листинга перед созданием storage
+pub fn init_sell(
+  sell: &mut Sell,
+  duration: u64
+) -> ProgramResult {
+  let cl = Clock::get()?;
+  sell.version = SELL_VERSION;
+  sell.start_time = cl.unix_timestamp as u64;
+  sell.end_time = 0;
+  // история ставок подключается отдельно после создания листинга
+  sell.bid_history = false;
+
+  // поля закрытого аукциона, кроме режима 3
+  if sell.auction != 3 {
+    sell.price_rule = 0;
+    sell.reveal_time = 0;
+    sell.second_price = 0;
+  }
+  // отзыв ставки есть только у аукциона
+  if sell.auction != 1 {
+    sell.withdraw_policy = 0;
+  }
+
+  if sell.auction == 0 { // SELL TOKEN
+    // цена за единицу, листинг продается частями
+    if sell.quantity == 0 { return Err(NftError::WrongSellSettings.into()); }
+    // срок листинга необязателен
+    if duration > 0 {
+      sell.end_time = sell.start_time.checked_add(duration).ok_or(NftError::WrongSellSettings)?;
+    }
+    sell.buy_now_price = 0;
+    sell.decay_step = 0;
+    sell.extend_time = 0;
+    sell.extend_window = 0;
+    sell.min_increment_bps = 0;
+    sell.reserve_price = 0;
+    return Ok(());
+  }
+
+  // аукционы продают один токен
+  sell.quantity = 1;
+
+  // аукцион без срока окончания не создаем
+  if duration == 0 { return Err(NftError::WrongSellSettings.into()); }
+  sell.end_time = sell.start_time.checked_add(duration).ok_or(NftError::WrongSellSettings)?;
+
+  if sell.auction == 1 { //AUCTION
+    // продление задается парой: окно и на сколько продлевать
+    if (sell.extend_window == 0) != (sell.extend_time == 0) { return Err(NftError::WrongSellSettings.into()); }
+    if sell.extend_window > duration { return Err(NftError::WrongSellSettings.into()); }
+
+    // шаг ставки от 0.01% до 100%
+    if sell.min_increment_bps == 0 || sell.min_increment_bps > 10000 { return Err(NftError::WrongSellSettings.into()); }
+
+    if sell.withdraw_policy > 2 { return Err(NftError::WrongSellSettings.into()); }
+
+    // цена выкупа выше стартовой и резерва
+    if sell.buy_now_price > 0 && (sell.buy_now_price <= sell.start_price || sell.buy_now_price < sell.reserve_price) {
+      return Err(NftError::WrongSellSettings.into());
+    }
+
+    sell.allowed_buyer = None;
+    sell.decay_step = 0;
+    return Ok(());
+  }
+
+  if sell.auction == 2 { // DUTCH AUCTION
+    // цена снижается от start_price до reserve_price
+    if sell.reserve_price >= sell.start_price { return Err(NftError::WrongSellSettings.into()); }
+    if sell.decay_step > duration { return Err(NftError::WrongSellSettings.into()); }
+
+    sell.allowed_buyer = None;
+    sell.buy_now_price = 0;
+    sell.price = sell.start_price;
+    sell.extend_time = 0;
+    sell.extend_window = 0;
+    sell.min_increment_bps = 0;
+    return Ok(());
+  }
+
+  if sell.auction == 3 { // SEALED-BID AUCTION
+    // депозиты ставок лежат в SOL на аккаунтах ставок
+    if sell.payment_mint.is_some() { return Err(NftError::WrongSellSettings.into()); }
+    if sell.reveal_time == 0 || sell.price_rule > 1 { return Err(NftError::WrongSellSettings.into()); }
+    // при второй цене и единственной ставке победитель платит резерв, без резерва токен ушел бы даром
+    if sell.price_rule == 1 && sell.reserve_price == 0 { return Err(NftError::WrongSellSettings.into()); }
+    add(sell.end_time, sell.reveal_time).map_err(|_| NftError::WrongSellSettings)?;
+
+    sell.allowed_buyer = None;
+    sell.buy_now_price = 0;
+    sell.decay_step = 0;
+    sell.extend_time = 0;
+    sell.extend_window = 0;
+    sell.min_increment_bps = 0;
+    sell.price = 0;
+    sell.second_price = sell.reserve_price;
+    return Ok(());
+  }
+
+  Err(NftError::WrongSellSettings.into())
+}
@@ -0,0 +1,198 @@
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  pubkey::Pubkey,
+  program_error::ProgramError,
+  sysvar::{clock::Clock, Sysvar}
+};
+use crate::{
+  STORAGE, SHARE_SEED, ESCROW,
+  error::NftError,
+  types::{
+    sell::Sell,
+    fee::FeeConfig,
+    event::Event
+  },
+  token::{
+    transfer_token_seed::process_transfer_token_seed,
+    token_program::unpack_account
+  },
+  utils::{
+    sell_storage::load_sell,
+    sell_withdrawal::check_sell_pda,
+    payment::{Payment, load_payment, pay_out, close_escrow},
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::close_registry,
+    event::emit,
+    amount::{bps_of, add, sub, close_lamports}
+  }
+};
+
+// закрытие аукциона после окончания, вызвать может кто угодно
+pub fn process_sell_settle<'a>(
+  program_id: &Pubkey,
+  payer: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if !payer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+
+  let (calc_profit, _) = Pubkey::find_program_address(
+    &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
+  );
+  if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
+
+  let settings = load_sell(storage)?;
+  if settings.auction != 1 { return Err(ProgramError::InvalidArgument); }
+  // старый аукцион без срока закрывает только продавец
+  if settings.version == 0 && settings.seller != *payer.key { return Err(NftError::LegacyListing.into()); }
+
+  let vault_seed = check_sell_pda(program_id, &settings, mint, vault, storage)?;
+  let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
+
+  let now = Clock::get()?.unix_timestamp as u64;
+  if now < settings.end_time { return Err(NftError::AuctionNotEnded.into()); }
+
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &settings, storage, payer, seller, applicant, treasury, account_info_iter)?;
+  let escrow_signer_seeds = &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[payment.escrow_seed]];
+
+  settle_auction(
+    program_id,
+    &settings,
+    payer,
+    mint,
+    seller,
+    applicant,
+    token_transfer_account,
+    vault,
+    storage,
+    profit_id,
+    token_program,
+    spl_token_program,
+    rent_program,
+    system_program,
+    vault_signer_seeds,
+    &config,
+    &payment,
+    escrow_signer_seeds,
+    metadata_account,
+    registry,
+    account_info_iter.as_slice()
+  )
+}
+
+// токен победителю (или продавцу, если ставок не было или не достигнут резерв), ставку продавцу, storage закрываем
+pub fn settle_auction<'a>(
+  program_id: &Pubkey,
+  settings: &Sell,
+  payer: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  seller: &AccountInfo<'a>,
+  applicant: &AccountInfo<'a>,
+  token_transfer_account: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  profit_id: &AccountInfo<'a>,
+  token_program: &AccountInfo<'a>,
+  spl_token_program: &AccountInfo<'a>,
+  rent_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  vault_signer_seeds: &[&[u8]],
+  config: &FeeConfig,
+  payment: &Payment<'a, '_>,
+  escrow_signer_seeds: &[&[u8]],
+  metadata_account: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  creator_accounts: &[AccountInfo<'a>]
+) -> ProgramResult {
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+  if settings.applicant != *applicant.key { return Err(ProgramError::InvalidArgument); }
+
+  // ставок не было или резерв не достигнут - токен возвращается продавцу
+  let has_bets = settings.applicant != settings.seller;
+  let reserve_met = settings.price >= settings.reserve_price;
+  let winner = if has_bets && reserve_met { applicant } else { seller };
+
+  msg!("Transfer token to auction winner and close auction");
+  process_transfer_token_seed(
+    payer,
+    winner,
+    mint,
+    vault,
+    token_transfer_account,
+    profit_id,
+    token_program,
+    rent_program,
+    system_program,
+    spl_token_program,
+    vault_signer_seeds,
+    1
+  )?;
+
+  // проверяем токен аккаунт
+  let spl_token_account = unpack_account(token_transfer_account)?;
+  if spl_token_account.owner != *winner.key { return Err(ProgramError::InvalidArgument); }
+
+  if has_bets && reserve_met { // значит были ставки и на аккаунте есть деньги
+    msg!("Transfer royalties");
+    let mut royalty: u64 = 0;
+    for (creator, amount) in royalties(&settings.payment_mint, mint, metadata_account, &mut creator_accounts.iter(), settings.price)? {
+      pay_out(payment.mint, payment.escrow, creator, amount, token_program, escrow_signer_seeds)?;
+      royalty = add(royalty, amount)?;
+    }
+
+    msg!("Transfer fee");
+    let fee_amount = bps_of(settings.price, config.fee_bps)?;
+    pay_out(payment.mint, payment.escrow, payment.treasury, fee_amount, token_program, escrow_signer_seeds)?;
+
+    // переводим ставку за токен продавцу
+    let am = sub(sub(settings.price, fee_amount)?, royalty)?;
+    pay_out(payment.mint, payment.escrow, payment.seller, am, token_program, escrow_signer_seeds)?;
+
+    emit(Event::SaleSettled {
+      buyer: settings.applicant,
+      price: settings.price,
+      seller: settings.seller,
+      storage: *storage.key
+    })?;
+  }
+  else if has_bets {
+    msg!("Reserve price not met, refund bet");
+    pay_out(payment.mint, payment.escrow, payment.applicant, settings.price, token_program, escrow_signer_seeds)?;
+
+    emit(Event::BidRefunded {
+      amount: settings.price,
+      bidder: settings.applicant,
+      storage: *storage.key
+    })?;
+  }
+
+  close_escrow(payment, seller, token_program, escrow_signer_seeds)?;
+
+  close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+  msg!("Close storage");
+  close_lamports(storage, profit_id)?;
+
+  msg!("Auction done");
+  Ok(())
+}
@@ -0,0 +1,77 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  program_error::ProgramError
+};
+use crate::{
+  SELL_VERSION, LEGACY_SELL_LEN,
+  error::NftError,
+  types::sell::{Sell, LegacySell}
+};
+
+// настройки листинга из storage, старый формат без версии читается с пустыми новыми полями
+pub fn load_sell(storage: &AccountInfo) -> Result<Sell, ProgramError> {
+  let data = storage.data.borrow();
+  if data.len() == LEGACY_SELL_LEN {
+    let legacy = LegacySell::try_from_slice(&data)?;
+    return Ok(from_legacy(legacy));
+  }
+
+  let settings = Sell::try_from_slice(&data)?;
+  if settings.version != SELL_VERSION { return Err(NftError::UnknownSellVersion.into()); }
+  Ok(settings)
+}
+
+// старый storage меньше нового формата, в него записываются только старые поля
+pub fn save_sell(settings: &Sell, storage: &AccountInfo) -> ProgramResult {
+  match settings.version {
+    0 => to_legacy(settings).serialize(&mut &mut storage.data.borrow_mut()[..])?,
+    SELL_VERSION => settings.serialize(&mut &mut storage.data.borrow_mut()[..])?,
+    _ => return Err(NftError::UnknownSellVersion.into())
+  }
+  Ok(())
+}
+
+fn to_legacy(settings: &Sell) -> LegacySell {
+  LegacySell {
+    applicant: settings.applicant,
+    auction: settings.auction,
+    bets: settings.bets,
+    price: settings.price,
+    seller: settings.seller,
+    start_price: settings.start_price,
+    vault: settings.vault
+  }
+}
+
+// у старого аукциона нет срока: новые ставки не принимаются, закрыть его может только продавец
+// претендент может отозвать ставку в любой момент, как и до версионирования
+fn from_legacy(legacy: LegacySell) -> Sell {
+  Sell {
+    version: 0,
+    allowed_buyer: None,
+    applicant: legacy.applicant,
+    auction: legacy.auction,
+    bets: legacy.bets,
+    bid_history: false,
+    buy_now_price: 0,
+    decay_step: 0,
+    end_time: 0,
+    extend_time: 0,
+    extend_window: 0,
+    min_increment_bps: 0,
+    payment_mint: None,
+    price: legacy.price,
+    price_rule: 0,
+    quantity: 1,
+    reserve_price: 0,
+    reveal_time: 0,
+    second_price: 0,
+    seller: legacy.seller,
+    start_price: legacy.start_price,
+    start_time: 0,
+    vault: legacy.vault,
+    withdraw_policy: 0
+  }
+}
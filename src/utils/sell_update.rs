@@ -0,0 +1,58 @@
+use solana_program::{
+  msg,
+  account_info::AccountInfo,
+  entrypoint::ProgramResult,
+  pubkey::Pubkey,
+  program_error::ProgramError,
+  sysvar::{clock::Clock, Sysvar}
+};
+use crate::{
+  error::NftError,
+  utils::{
+    sell_storage::{load_sell, save_sell},
+    sell_withdrawal::{check_sell_pda, sell_expired}
+  }
+};
+
+// смена цены без снятия с продажи
+pub fn process_sell_update<'a>(
+  program_id: &Pubkey,
+  seller: &AccountInfo<'a>,
+  mint: &AccountInfo<'a>,
+  vault: &AccountInfo<'a>,
+  storage: &AccountInfo<'a>,
+  price: u64
+) -> ProgramResult {
+  if !seller.is_signer { return Err(ProgramError::MissingRequiredSignature); }
+  if price == 0 { return Err(NftError::WrongSellSettings.into()); }
+
+  let mut settings = load_sell(storage)?;
+  if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
+
+  check_sell_pda(program_id, &settings, mint, vault, storage)?;
+
+  let now = Clock::get()?.unix_timestamp as u64;
+
+  if settings.auction == 0 { // SELL TOKEN
+    if sell_expired(&settings, now) { return Err(NftError::ListingExpired.into()); }
+    settings.price = price;
+  }
+  else if settings.auction == 1 { //AUCTION
+    // стартовую цену можно менять только до первой ставки
+    if settings.bets > 0 { return Err(NftError::WrongSellSettings.into()); }
+
+    if now >= settings.end_time { return Err(NftError::AuctionEnded.into()); }
+    if settings.buy_now_price > 0 && price >= settings.buy_now_price { return Err(NftError::WrongSellSettings.into()); }
+
+    settings.start_price = price;
+    settings.price = price;
+  }
+  else {
+    return Err(NftError::WrongSellSettings.into());
+  }
+
+  save_sell(&settings, storage)?;
+
+  msg!("Listing price updated");
+  Ok(())
+}
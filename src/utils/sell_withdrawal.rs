@@ -1,20 +1,255 @@
-use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
   msg,
   account_info::AccountInfo,
   entrypoint::ProgramResult, 
-  program::{invoke},
   pubkey::Pubkey,
-  system_instruction,
-  program_error::ProgramError
+  program_error::ProgramError,
+  sysvar::{clock::Clock, Sysvar}
 };
 use crate::{
-  STORAGE, SHARE_SEED,
-  types::sell::Sell,
-  token::transfer_token_seed::process_transfer_token_seed
+  STORAGE, SHARE_SEED, ESCROW,
+  error::NftError,
+  types::{
+    sell::Sell,
+    fee::FeeConfig,
+    event::Event
+  },
+  token::transfer_token_seed::process_transfer_token_seed,
+  utils::{
+    sell_storage::{load_sell, save_sell},
+    sell_settle::settle_auction,
+    bid_history::record_bid,
+    payment::{Payment, load_payment, pay, pay_out},
+    royalty::royalties,
+    fee_config::load_fee_config,
+    registry::close_registry,
+    event::emit,
+    amount::{bps_of, mul_div, add, mul, sub, close_lamports}
+  }
 };
-use spl_token::state::Account;
-use solana_program::program_pack::Pack;
+
+// проверка vault и storage листинга, возвращает seed для подписи vault
+pub fn check_sell_pda(
+  program_id: &Pubkey,
+  settings: &Sell,
+  mint: &AccountInfo,
+  vault: &AccountInfo,
+  storage: &AccountInfo
+) -> Result<u8, ProgramError> {
+  if settings.vault != *vault.key { return Err(ProgramError::InvalidArgument); }
+
+  let (calc_vault, vault_seed) = Pubkey::find_program_address(
+    &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref()], &program_id
+  );
+  if calc_vault != *vault.key { return Err(ProgramError::InvalidArgument); }
+
+  let (calc_storage, _) = Pubkey::find_program_address(
+    &[STORAGE.as_bytes(), program_id.as_ref(), vault.key.as_ref()], &program_id 
+  );
+  if calc_storage != *storage.key { return Err(ProgramError::InvalidArgument); }
+
+  Ok(vault_seed)
+}
+
+// текущая цена голландского аукциона: от start_price до reserve_price за время листинга
+pub fn dutch_price(settings: &Sell, now: u64) -> Result<u64, ProgramError> {
+  if now >= settings.end_time { return Ok(settings.reserve_price); }
+
+  let duration = sub(settings.end_time, settings.start_time)?;
+  let mut elapsed = now.saturating_sub(settings.start_time);
+  if settings.decay_step > 0 { // цена падает ступенями
+    elapsed -= elapsed % settings.decay_step;
+  }
+
+  let drop = mul_div(sub(settings.start_price, settings.reserve_price)?, elapsed, duration)?;
+  sub(settings.start_price, drop)
+}
+
+// срок фиксированного листинга истек
+pub fn sell_expired(settings: &Sell, now: u64) -> bool {
+  settings.auction == 0 && settings.end_time != 0 && now >= settings.end_time
+}
+
+// проверки покупки по фиксированной цене: срок и приватный покупатель
+pub fn check_buyer(settings: &Sell, buyer: &Pubkey, now: u64) -> ProgramResult {
+  if settings.seller == *buyer { return Ok(()); }
+
+  if sell_expired(settings, now) { return Err(NftError::ListingExpired.into()); }
+
+  // приватная продажа только указанному покупателю
+  if let Some(allowed_buyer) = settings.allowed_buyer {
+    if allowed_buyer != *buyer { return Err(NftError::BuyerNotAllowed.into()); }
+  }
+
+  Ok(())
+}
+
+// оплата покупки: комиссия, роялти создателям, остаток продавцу
+pub fn pay_sale<'a>(
+  config: &FeeConfig,
+  price: u64,
+  buyer: &AccountInfo<'a>,
+  payment: &Payment<'a, '_>,
+  payouts: &[(&AccountInfo<'a>, u64)],
+  token_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>
+) -> ProgramResult {
+  msg!("Trafsfer fee");
+  pay(
+    payment.mint,
+    buyer,
+    payment.buyer,
+    payment.treasury,
+    bps_of(price, config.fee_bps)?,
+    token_program,
+    system_program
+  )?;
+
+  msg!("Transfer royalties");
+  let mut royalty: u64 = 0;
+  for (creator, amount) in payouts.iter() {
+    pay(
+      payment.mint,
+      buyer,
+      payment.buyer,
+      creator,
+      *amount,
+      token_program,
+      system_program
+    )?;
+    royalty = add(royalty, *amount)?;
+  }
+
+  msg!("Payment for token");
+  pay(
+    payment.mint,
+    buyer,
+    payment.buyer,
+    payment.seller,
+    sub(price, royalty)?,
+    token_program,
+    system_program
+  )
+}
+
+// можно ли претенденту отозвать ставку по правилу листинга, возвращает штраф в bps
+pub fn withdraw_penalty_bps(settings: &Sell, config: &FeeConfig, now: u64) -> Result<u16, ProgramError> {
+  // в старом аукционе ставку можно было отозвать в любой момент с комиссией
+  if settings.version == 0 { return Ok(config.cancel_penalty_bps); }
+
+  let ended = now >= settings.end_time;
+  match settings.withdraw_policy {
+    1 if !ended => Ok(config.cancel_penalty_bps),
+    2 if ended => Ok(0),
+    _ => Err(NftError::BetWithdrawalNotAllowed.into())
+  }
+}
+
+// отмена ставки претендентом, цена последней ставки остается нижней границей для следующей
+pub fn cancel_bet<'a>(
+  settings: &mut Sell,
+  storage: &AccountInfo<'a>,
+  penalty_bps: u16,
+  payment: &Payment<'a, '_>,
+  token_program: &AccountInfo<'a>,
+  escrow_signer_seeds: &[&[u8]]
+) -> ProgramResult {
+  // штраф за отмену уходит в treasury
+  let penalty = bps_of(settings.price, penalty_bps)?;
+  pay_out(
+    payment.mint,
+    payment.escrow,
+    payment.applicant,
+    sub(settings.price, penalty)?,
+    token_program,
+    escrow_signer_seeds
+  )?;
+  pay_out(
+    payment.mint,
+    payment.escrow,
+    payment.treasury,
+    penalty,
+    token_program,
+    escrow_signer_seeds
+  )?;
+
+  emit(Event::BidRefunded {
+    amount: sub(settings.price, penalty)?,
+    bidder: settings.applicant,
+    storage: *storage.key
+  })?;
+
+  settings.applicant = settings.seller;
+
+  Ok(())
+}
+
+// новая ставка: ставка переводится в storage (escrow), предыдущему участнику возвращается полностью
+pub fn place_bet<'a>(
+  settings: &mut Sell,
+  storage: &AccountInfo<'a>,
+  buyer: &AccountInfo<'a>,
+  payment: &Payment<'a, '_>,
+  now: u64,
+  token_program: &AccountInfo<'a>,
+  system_program: &AccountInfo<'a>,
+  escrow_signer_seeds: &[&[u8]]
+) -> ProgramResult {
+  let price: u64;
+  if settings.seller != settings.applicant {
+    let step = bps_of(settings.price, settings.min_increment_bps)?;
+    price = add(settings.price, step.max(1))?;
+  }// сразу повышаем цену на шаг ставки
+  else {
+    price = settings.price;
+  }
+
+  // переводим на сторадж (escrow) текущую стоимость токена
+  pay(
+    payment.mint,
+    buyer,
+    payment.buyer,
+    payment.escrow,
+    price,
+    token_program,
+    system_program
+  )?;
+
+  if settings.seller != settings.applicant {// возвращаем предыдущему участнику ставку
+    pay_out(
+      payment.mint,
+      payment.escrow,
+      payment.applicant,
+      settings.price,
+      token_program,
+      escrow_signer_seeds
+    )?;
+
+    emit(Event::BidRefunded {
+      amount: settings.price,
+      bidder: settings.applicant,
+      storage: *storage.key
+    })?;
+  }
+
+  emit(Event::BidPlaced {
+    amount: price,
+    bidder: *buyer.key,
+    storage: *storage.key
+  })?;
+
+  settings.price = price;
+  settings.applicant = *buyer.key;
+  settings.bets = add(settings.bets, 1)?;
+
+  // ставка в последние секунды продлевает аукцион
+  if settings.extend_window > 0 && add(now, settings.extend_window)? >= settings.end_time {
+    settings.end_time = add(settings.end_time, settings.extend_time)?;
+    msg!("Auction extended");
+  }
+
+  Ok(())
+}
 
 pub fn process_sell_withdrawal<'a>(
   program_id: &Pubkey,
@@ -29,9 +264,13 @@ pub fn process_sell_withdrawal<'a>(
   token_program: &AccountInfo<'a>,
   spl_token_program: &AccountInfo<'a>,
   rent_program: &AccountInfo<'a>,
-  system_program: &AccountInfo<'a>
+  system_program: &AccountInfo<'a>,
+  metadata_account: &AccountInfo<'a>,
+  fee_config: &AccountInfo<'a>,
+  treasury: &AccountInfo<'a>,
+  registry: &AccountInfo<'a>,
+  remaining_accounts: &[AccountInfo<'a>]
 ) -> ProgramResult {
-  // проверку на апликанта сделать!!!
   if !buyer.is_signer { return Err(ProgramError::MissingRequiredSignature); }
 
   // проверка профит айди
@@ -39,36 +278,39 @@ pub fn process_sell_withdrawal<'a>(
     &[SHARE_SEED.as_bytes(), program_id.as_ref(), program_id.as_ref()], &program_id
   );
   if calc_profit != *profit_id.key { return Err(ProgramError::InvalidArgument); }
+
+  let config = load_fee_config(program_id, fee_config, treasury)?;
   
-  let mut settings = Sell::try_from_slice(&storage.data.borrow())?;
-  if settings.vault != *vault.key { return Err(ProgramError::InvalidArgument); }
+  let mut settings = load_sell(storage)?;
   if settings.seller != *seller.key { return Err(ProgramError::InvalidArgument); }
 
-  let (calc_vault, vault_seed) = Pubkey::find_program_address(
-    &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref()], &program_id
-  );
-  if calc_vault != *vault.key { return Err(ProgramError::InvalidArgument); }
+  let vault_seed = check_sell_pda(program_id, &settings, mint, vault, storage)?;
   let vault_signer_seeds = &[STORAGE.as_bytes(), program_id.as_ref(), mint.key.as_ref(), &[vault_seed]];
 
-  let (calc_storage, _) = Pubkey::find_program_address(
-    &[STORAGE.as_bytes(), program_id.as_ref(), vault.key.as_ref()], &program_id 
-  );
-  if calc_storage != *storage.key { return Err(ProgramError::InvalidArgument); }
-  
+  // после счетов оплаты идут аккаунты создателей для роялти
+  let account_info_iter = &mut remaining_accounts.iter();
+  let payment = load_payment(program_id, &settings, storage, buyer, seller, applicant, treasury, account_info_iter)?;
+  let escrow_signer_seeds = &[ESCROW.as_bytes(), program_id.as_ref(), storage.key.as_ref(), &[payment.escrow_seed]];
 
-  if settings.auction == 0 { // SELL TOKEN
-    
-    if settings.seller != *buyer.key {
-      msg!("Trafsfer fee");
-      invoke(
-        &system_instruction::transfer(buyer.key, profit_id.key, (settings.price as f32 * 0.01) as u64),
-        &[buyer.clone(), profit_id.clone(), system_program.clone()]
-      )?;
+  let now = Clock::get()?.unix_timestamp as u64;
+
+  if settings.auction == 0 || settings.auction == 2 { // SELL TOKEN
+    let unit_price = if settings.auction == 2 { dutch_price(&settings, now)? } else { settings.price };
+    // покупается весь остаток листинга
+    let price = mul(unit_price, settings.quantity)?;
 
-      msg!("Payment for token");
-      invoke(
-        &system_instruction::transfer(buyer.key, seller.key, settings.price),
-        &[buyer.clone(), seller.clone(), system_program.clone()]
+    check_buyer(&settings, buyer.key, now)?;
+
+    if settings.seller != *buyer.key {
+      let payouts = royalties(&settings.payment_mint, mint, metadata_account, account_info_iter, price)?;
+      pay_sale(
+        &config,
+        price,
+        buyer,
+        &payment,
+        &payouts,
+        token_program,
+        system_program
       )?;
     }
     
@@ -84,123 +326,95 @@ pub fn process_sell_withdrawal<'a>(
       rent_program,
       system_program,
       spl_token_program,
-      vault_signer_seeds
+      vault_signer_seeds,
+      settings.quantity
     )?;
     
-    let dest_starting_lamports = profit_id.lamports();
-    **profit_id.lamports.borrow_mut() = dest_starting_lamports
-      .checked_add(storage.lamports())
-      .unwrap();
-    **storage.lamports.borrow_mut() = 0;
+    if settings.seller != *buyer.key {
+      emit(Event::SaleSettled {
+        buyer: *buyer.key,
+        price,
+        seller: settings.seller,
+        storage: *storage.key
+      })?;
+    }
+
+    close_registry(program_id, mint.key, storage.key, registry, seller)?;
+
+    close_lamports(storage, profit_id)?;
 
     msg!("Process withdrawal done");
     Ok(())
   }
   else { //AUCTION
+    if settings.auction != 1 { return Err(ProgramError::InvalidArgument); }
+    if settings.applicant != *applicant.key { return Err(ProgramError::InvalidArgument); }
+
     // вывод токена или денег продавцом (владельцем)
     if settings.seller == *buyer.key {// запрос владельца токена
+      // со ставками аукцион можно закрыть только после окончания
+      if settings.applicant != settings.seller && now < settings.end_time {
+        return Err(NftError::AuctionNotEnded.into());
+      }
+
       // если не было ставок, просто возвращаем токен
-      // если были ставки, проверить чтобы совпадал token_transfer_account с аппликантом
-      msg!("Transfer token to auction owner and close auction");
-      process_transfer_token_seed(
+      settle_auction(
+        program_id,
+        &settings,
         buyer,
-        applicant,
         mint,
-        vault,
+        seller,
+        applicant,
         token_transfer_account,
+        vault,
+        storage,
         profit_id,
         token_program,
+        spl_token_program,
         rent_program,
         system_program,
-        spl_token_program,
-        vault_signer_seeds
-      )?;
-
-      let spl_token_account_data = &token_transfer_account.try_borrow_data()?;
-      let spl_token_account = Account::unpack(&spl_token_account_data)?;
-
-      // проверяем токен аккаунт
-      if settings.applicant != *buyer.key {
-        if spl_token_account.owner != settings.applicant {
-          return Err(ProgramError::InvalidArgument);
-        }
-      }
-      else {
-        if spl_token_account.owner != settings.seller {
-          return Err(ProgramError::InvalidArgument);
-        }
-      }
-      
-      if settings.applicant != *buyer.key { // значит были ставки и на аккаунте есть деньги
-        // переводим ставку за токен продавцу, т.к. seller - это продавец
-        let am = settings.price - (settings.price as f32 * 0.01) as u64;
-        let dest_starting_lamports = seller.lamports();
-        **seller.lamports.borrow_mut() = dest_starting_lamports.checked_add(am).unwrap();
-        **storage.lamports.borrow_mut() = storage.lamports() - am;
-      }
-      
-      msg!("Close storage");
-      let dest_starting_lamports = profit_id.lamports();
-      **profit_id.lamports.borrow_mut() = dest_starting_lamports
-        .checked_add(storage.lamports())
-        .unwrap();
-      **storage.lamports.borrow_mut() = 0;
-
-      msg!("Auction done");
-      Ok(())
+        vault_signer_seeds,
+        &config,
+        &payment,
+        escrow_signer_seeds,
+        metadata_account,
+        registry,
+        account_info_iter.as_slice()
+      )
     }
     else {// значит запрос претендента
-
       if settings.applicant == *buyer.key {
-        msg!("Cancel bet");
-        let dest_starting_lamports = applicant.lamports();
-        let ret = settings.price - 1000000;
+        // отзыв ставки по правилу листинга
+        let penalty_bps = withdraw_penalty_bps(&settings, &config, now)?;
 
-        **applicant.lamports.borrow_mut() = dest_starting_lamports
-          .checked_add(ret)
-          .unwrap();
-        **storage.lamports.borrow_mut() = storage.lamports() - ret;
-
-        settings.price = settings.start_price;
-        settings.applicant = settings.seller;
-        settings.bets = 0;
-
-        let _ = settings.serialize(&mut &mut storage.data.borrow_mut()[..]);
+        msg!("Cancel bet");
+        cancel_bet(&mut settings, storage, penalty_bps, &payment, token_program, escrow_signer_seeds)?;
 
-        msg!("Auction restart");
+        save_sell(&settings, storage)?;
 
+        msg!("Bet withdrawn");
         Ok(())
       }
       else {
-        msg!("Start new bet");
+        // после окончания доступно только закрытие аукциона
+        if now >= settings.end_time { return Err(NftError::AuctionEnded.into()); }
 
-        let price: u64;
-        if settings.seller != settings.applicant {
-          price = settings.price + (settings.price / 100);
-        }// сразу повышаем цену чтобы покупатель оплатил + 1%;
-        else {
-          price = settings.price;
-        }
-        invoke(// переводим на сторадж текущую стоимость токена
-          &system_instruction::transfer(buyer.key, storage.key, price),
-          &[buyer.clone(), storage.clone(), system_program.clone()]
+        msg!("Start new bet");
+        place_bet(
+          &mut settings,
+          storage,
+          buyer,
+          &payment,
+          now,
+          token_program,
+          system_program,
+          escrow_signer_seeds
         )?;
 
-        if settings.seller != settings.applicant {// возвращаем предыдущему участнику sol
-          let dest_starting_lamports = applicant.lamports();
-          let ret = settings.price - 1000000;
-
-          **applicant.lamports.borrow_mut() = dest_starting_lamports
-            .checked_add(ret)
-            .unwrap();
-          **storage.lamports.borrow_mut() = storage.lamports() - ret;
-        }
-
-        settings.price = price;
-        settings.applicant = *buyer.key;
-        settings.bets = settings.bets + 1;
+        // после счетов оплаты идет аккаунт истории ставок, если она создана
+        record_bid(program_id, &settings, storage, account_info_iter, now)?;
 
-        let _ = settings.serialize(&mut &mut storage.data.borrow_mut()[..]);
+        save_sell(&settings, storage)?;
 
         msg!("New bet done");
         Ok(())
@@ -209,4 +423,4 @@ pub fn process_sell_withdrawal<'a>(
     }
     
   }
-}
\ No newline at end of file
+}